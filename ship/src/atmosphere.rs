@@ -0,0 +1,190 @@
+// atmosphere.rs
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+use std::f32::consts::PI;
+
+// Number of samples along the view ray and along each light ray
+const VIEW_SAMPLES: usize = 8;
+const LIGHT_SAMPLES: usize = 4;
+
+/// Single-scattering atmosphere shell around a body.
+/// All distances are in the body's model space, where the planet mesh has radius `planet_radius`.
+#[derive(Clone)]
+pub struct Atmosphere {
+    pub planet_radius: f32,              // Radius of the solid surface
+    pub radius: f32,                     // Outer radius of the atmosphere shell
+    pub rayleigh_scale_height: f32,      // Height where Rayleigh density falls to 1/e
+    pub mie_scale_height: f32,           // Height where Mie density falls to 1/e
+    pub rayleigh_coefficients: Vector3,  // Rayleigh scattering per RGB channel at sea level
+    pub mie_coefficient: f32,            // Mie scattering at sea level (wavelength independent)
+    pub mie_anisotropy: f32,             // Henyey-Greenstein g, > 0 scatters forward
+    pub sun_intensity: f32,
+}
+
+impl Atmosphere {
+    /// Earth-like atmosphere, exaggerated in thickness so the limb is visible from orbit
+    pub fn earth() -> Self {
+        Atmosphere {
+            planet_radius: 0.5,
+            radius: 0.575,
+            rayleigh_scale_height: 0.02,
+            mie_scale_height: 0.006,
+            rayleigh_coefficients: Vector3::new(2.3, 5.4, 13.2),
+            mie_coefficient: 4.2,
+            mie_anisotropy: 0.76,
+            sun_intensity: 22.0,
+        }
+    }
+
+    /// Thick hydrogen/methane atmosphere: red is absorbed, leaving a cyan haze
+    pub fn uranus() -> Self {
+        Atmosphere {
+            planet_radius: 0.5,
+            radius: 0.56,
+            rayleigh_scale_height: 0.025,
+            mie_scale_height: 0.01,
+            rayleigh_coefficients: Vector3::new(1.2, 6.5, 8.0),
+            mie_coefficient: 1.5,
+            mie_anisotropy: 0.6,
+            sun_intensity: 18.0,
+        }
+    }
+
+    /// Builds the shell mesh by pushing the planet vertices out to the atmosphere radius
+    pub fn shell_vertices(&self, planet_vertices: &[Vertex]) -> Vec<Vertex> {
+        let scale = self.radius / self.planet_radius;
+        planet_vertices
+            .iter()
+            .map(|vertex| Vertex::new_with_tangent(vertex.position * scale, vertex.normal, vertex.tex_coords, vertex.tangent))
+            .collect()
+    }
+
+    /// Densities (Rayleigh, Mie) relative to sea level at a point in model space
+    fn density(&self, point: Vector3) -> (f32, f32) {
+        let height = (point.length() - self.planet_radius).max(0.0);
+        (
+            (-height / self.rayleigh_scale_height).exp(),
+            (-height / self.mie_scale_height).exp(),
+        )
+    }
+
+    /// Optical depth (Rayleigh, Mie) from `point` to the edge of the atmosphere towards `light_dir`.
+    /// Returns None when the planet blocks the light.
+    fn light_optical_depth(&self, point: Vector3, light_dir: Vector3) -> Option<(f32, f32)> {
+        if let Some((t_near, _)) = ray_sphere_intersect(point, light_dir, self.planet_radius)
+            && t_near > 0.0
+        {
+            return None;
+        }
+
+        let (_, t_far) = ray_sphere_intersect(point, light_dir, self.radius)?;
+        let step = t_far.max(0.0) / LIGHT_SAMPLES as f32;
+
+        let mut depth_rayleigh = 0.0;
+        let mut depth_mie = 0.0;
+        for i in 0..LIGHT_SAMPLES {
+            let sample = point + light_dir * ((i as f32 + 0.5) * step);
+            let (rayleigh, mie) = self.density(sample);
+            depth_rayleigh += rayleigh * step;
+            depth_mie += mie * step;
+        }
+
+        Some((depth_rayleigh, depth_mie))
+    }
+
+    /// Light scattered towards `eye` along the view ray through `point`.
+    /// `eye`, `point` and `light_position` are in model space.
+    pub fn scatter(&self, eye: Vector3, point: Vector3, light_position: Vector3) -> Vector3 {
+        let view_dir = (point - eye).normalized();
+
+        // Segment of the view ray that lies inside the shell
+        let (mut t_start, mut t_end) = match ray_sphere_intersect(eye, view_dir, self.radius) {
+            Some(hit) => hit,
+            None => return Vector3::zero(),
+        };
+        t_start = t_start.max(0.0);
+
+        // Stop at the surface if the ray hits the planet
+        if let Some((t_planet, _)) = ray_sphere_intersect(eye, view_dir, self.planet_radius)
+            && t_planet > 0.0
+        {
+            t_end = t_end.min(t_planet);
+        }
+
+        if t_end <= t_start {
+            return Vector3::zero();
+        }
+
+        let step = (t_end - t_start) / VIEW_SAMPLES as f32;
+        let mie_extinction = self.mie_coefficient * 1.1;
+
+        let mut sum_rayleigh = Vector3::zero();
+        let mut sum_mie = Vector3::zero();
+        let mut view_depth_rayleigh = 0.0;
+        let mut view_depth_mie = 0.0;
+        let mut mu_sum = 0.0;
+        let mut lit_samples = 0;
+
+        for i in 0..VIEW_SAMPLES {
+            let sample = eye + view_dir * (t_start + (i as f32 + 0.5) * step);
+            let (rayleigh, mie) = self.density(sample);
+            view_depth_rayleigh += rayleigh * step;
+            view_depth_mie += mie * step;
+
+            let light_dir = (light_position - sample).normalized();
+            let Some((light_depth_rayleigh, light_depth_mie)) = self.light_optical_depth(sample, light_dir) else {
+                continue; // Sample in the planet's shadow
+            };
+
+            let tau = self.rayleigh_coefficients * (view_depth_rayleigh + light_depth_rayleigh)
+                + Vector3::one() * (mie_extinction * (view_depth_mie + light_depth_mie));
+            let attenuation = Vector3::new((-tau.x).exp(), (-tau.y).exp(), (-tau.z).exp());
+
+            sum_rayleigh += attenuation * (rayleigh * step);
+            sum_mie += attenuation * (mie * step);
+            mu_sum += view_dir.dot(light_dir);
+            lit_samples += 1;
+        }
+
+        if lit_samples == 0 {
+            return Vector3::zero();
+        }
+
+        // The sun is far compared to the shell, so one averaged angle is enough for the phase functions
+        let mu = mu_sum / lit_samples as f32;
+        let color = (sum_rayleigh * self.rayleigh_coefficients * rayleigh_phase(mu)
+            + sum_mie * (self.mie_coefficient * mie_phase(mu, self.mie_anisotropy)))
+            * self.sun_intensity;
+
+        // Exposure tone mapping keeps the bright limb from clipping to white
+        Vector3::new(
+            1.0 - (-color.x).exp(),
+            1.0 - (-color.y).exp(),
+            1.0 - (-color.z).exp(),
+        )
+    }
+}
+
+/// Intersects a ray with a sphere centered at the origin.
+/// Returns the near and far distances along the ray, which may be negative.
+pub fn ray_sphere_intersect(origin: Vector3, dir: Vector3, radius: f32) -> Option<(f32, f32)> {
+    let b = origin.dot(dir);
+    let c = origin.dot(origin) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    Some((-b - root, -b + root))
+}
+
+fn rayleigh_phase(mu: f32) -> f32 {
+    3.0 / (16.0 * PI) * (1.0 + mu * mu)
+}
+
+// Cornette-Shanks variant of the Henyey-Greenstein phase function
+fn mie_phase(mu: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    3.0 / (8.0 * PI) * ((1.0 - g2) * (1.0 + mu * mu))
+        / ((2.0 + g2) * (1.0 + g2 - 2.0 * g * mu).powf(1.5))
+}
@@ -3,8 +3,27 @@
 
 use raylib::prelude::*;
 use crate::matrix::create_view_matrix;
+use crate::input::{Action, Bindings};
+use crate::viewport::Viewport;
 use std::f32::consts::PI;
 
+/// How the camera responds to input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    Orbit,   // Rotates around `target`, pitch limited
+    FreeFly, // Six degrees of freedom, oriented by a quaternion
+    Chase,   // Trails the ship with spring-damper lag
+    Cockpit, // Rides inside the ship at `cockpit_anchor`
+}
+
+/// How the camera reacts to a moving object
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tracking {
+    Free,   // Ignore the object
+    Follow, // Travel with the object, keeping the same offset
+    LockOn, // Stay in place and turn to keep the object centered
+}
+
 pub struct Camera {
     // Camera position/orientation
     pub eye: Vector3,        // Camera position
@@ -16,10 +35,45 @@ pub struct Camera {
     pub pitch: f32,          // Rotation around X axis (up/down)
     pub distance: f32,       // Distance from target
 
-    // Movement speed
-    pub rotation_speed: f32,
-    pub zoom_speed: f32,
-    pub pan_speed: f32,
+    // Movement speed (per second)
+    pub rotation_speed: f32, // radians per second
+    pub zoom_speed: f32,     // units per second
+    pub pan_speed: f32,      // units per second
+
+    // Smoothed velocities
+    pub yaw_velocity: f32,
+    pub pitch_velocity: f32,
+    pub zoom_velocity: f32,
+    pub pan_velocity: Vector2, // x: along the right vector, y: vertical
+
+    // How quickly velocity follows the input (1/s)
+    pub acceleration: f32,
+    pub damping: f32,
+
+    // Projection and mouse controls
+    pub fov: f32,               // Vertical field of view in radians
+    pub mouse_sensitivity: f32, // Radians of rotation per pixel dragged
+    pub wheel_zoom_step: f32,   // Fraction of the distance covered per wheel notch
+
+    // Free-fly camera parameters
+    pub mode: CameraMode,
+    pub orientation: Quaternion, // Rotation from camera space (looking down -Z) to world space
+    pub fly_velocity: Vector3,   // x: right, y: up, z: forward, in camera space
+    pub roll_velocity: f32,
+    pub thrust_speed: f32,       // units per second
+    pub roll_speed: f32,         // radians per second
+
+    // Tracking of a moving object
+    pub tracking: Tracking,
+    tracked_position: Option<Vector3>, // Where the object was last frame
+
+    // Ship cameras, offsets are in the ship's model space
+    pub chase_offset: Vector3,    // Where the chase camera wants to be
+    pub chase_look_ahead: f32,    // Distance ahead of the ship the chase camera looks at
+    pub chase_stiffness: f32,     // Spring constant (1/s²)
+    pub chase_damping: f32,       // Damping constant (1/s), 2·sqrt(stiffness) is critical damping
+    pub chase_velocity: Vector3,
+    pub cockpit_anchor: Vector3,  // Eye position for the cockpit view
 }
 
 impl Camera {
@@ -42,12 +96,200 @@ impl Camera {
             yaw,
             pitch,
             distance,
-            rotation_speed: 0.05,
-            zoom_speed: 0.5,
-            pan_speed: 0.1,
+            rotation_speed: 1.5,
+            zoom_speed: 30.0,
+            pan_speed: 6.0,
+            yaw_velocity: 0.0,
+            pitch_velocity: 0.0,
+            zoom_velocity: 0.0,
+            pan_velocity: Vector2::zero(),
+            acceleration: 8.0,
+            damping: 5.0,
+            fov: PI / 3.0,
+            mouse_sensitivity: 0.005,
+            wheel_zoom_step: 0.1,
+            mode: CameraMode::Orbit,
+            orientation: Quaternion::identity(),
+            fly_velocity: Vector3::zero(),
+            roll_velocity: 0.0,
+            thrust_speed: 20.0,
+            roll_speed: 1.2,
+            tracking: Tracking::Free,
+            tracked_position: None,
+            chase_offset: Vector3::new(-10.0, 3.5, 0.0),
+            chase_look_ahead: 6.0,
+            chase_stiffness: 30.0,
+            chase_damping: 11.0,
+            chase_velocity: Vector3::zero(),
+            cockpit_anchor: Vector3::new(-0.6, 1.35, 0.0),
+        }
+    }
+
+    /// Go to the next mode: orbit, free-fly, chase, cockpit and back to orbit.
+    /// The orbit and free-fly cameras start from the current view.
+    pub fn cycle_mode(&mut self) {
+        match self.mode {
+            CameraMode::Orbit => self.enter_free_fly(),
+            CameraMode::FreeFly => self.mode = CameraMode::Chase,
+            CameraMode::Chase => self.mode = CameraMode::Cockpit,
+            CameraMode::Cockpit => self.enter_orbit(),
+        }
+
+        self.stop();
+    }
+
+    fn enter_free_fly(&mut self) {
+        // The orbit camera never rolls, so heading and elevation describe its orientation
+        let forward = (self.target - self.eye).normalized();
+        let heading = (-forward.x).atan2(-forward.z);
+        let elevation = forward.y.clamp(-1.0, 1.0).asin();
+        self.orientation = Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), heading)
+            * Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), elevation);
+        self.mode = CameraMode::FreeFly;
+    }
+
+    fn enter_orbit(&mut self) {
+        // Orbit around the point the camera was looking at
+        self.target = self.eye + (self.target - self.eye).normalized() * self.distance;
+        self.up = Vector3::new(0.0, 1.0, 0.0);
+        self.sync_orbit();
+        self.update_eye_position();
+        self.mode = CameraMode::Orbit;
+    }
+
+    /// Drive the chase and cockpit cameras from the ship's model matrix. Call once per frame.
+    pub fn attach_to_ship(&mut self, model_matrix: Matrix, dt: f32) {
+        let to_world = |point: Vector3| point.transform_with(model_matrix);
+        let origin = to_world(Vector3::zero());
+        // The ship's nose points along +X and its canopy along +Y
+        let forward = (to_world(Vector3::new(1.0, 0.0, 0.0)) - origin).normalized();
+        let up = (to_world(Vector3::new(0.0, 1.0, 0.0)) - origin).normalized();
+
+        match self.mode {
+            CameraMode::Chase => {
+                // Large steps would make the spring overshoot and explode
+                let dt = dt.min(0.05);
+                let desired = to_world(self.chase_offset);
+                let acceleration = (desired - self.eye) * self.chase_stiffness - self.chase_velocity * self.chase_damping;
+                self.chase_velocity += acceleration * dt;
+                self.eye += self.chase_velocity * dt;
+                self.target = to_world(Vector3::new(self.chase_look_ahead, 0.0, 0.0));
+                self.up = up;
+            }
+            CameraMode::Cockpit => {
+                self.eye = to_world(self.cockpit_anchor);
+                self.target = self.eye + forward * self.distance;
+                self.up = up;
+            }
+            CameraMode::Orbit | CameraMode::FreeFly => {}
+        }
+    }
+
+    /// Place the orbit camera at `eye` looking at `target`, keeping the projection and control settings
+    pub fn look_at(&mut self, eye: Vector3, target: Vector3, up: Vector3) {
+        self.eye = eye;
+        self.target = target;
+        self.up = up;
+        self.sync_orbit();
+        self.mode = CameraMode::Orbit;
+        self.tracked_position = None;
+        self.stop();
+    }
+
+    /// Change the tracking mode; the object is picked up again on the next call to `track`
+    pub fn set_tracking(&mut self, tracking: Tracking) {
+        self.tracking = tracking;
+        self.tracked_position = None;
+    }
+
+    /// Apply the tracking mode to an object currently at `position`. Call once per frame after input.
+    pub fn track(&mut self, position: Vector3) {
+        // The ship cameras place themselves
+        if matches!(self.mode, CameraMode::Chase | CameraMode::Cockpit) {
+            return;
+        }
+
+        match self.tracking {
+            Tracking::Free => {}
+            Tracking::Follow => {
+                match self.tracked_position {
+                    // Move by as much as the object moved, user input still changes the offset
+                    Some(last) => {
+                        let delta = position - last;
+                        self.eye += delta;
+                        self.target += delta;
+                        if self.mode == CameraMode::FreeFly {
+                            self.update_free_fly_view();
+                        }
+                    }
+                    // Center the object when the follow starts
+                    None => {
+                        if self.mode == CameraMode::Orbit {
+                            self.target = position;
+                            self.sync_orbit();
+                        }
+                    }
+                }
+            }
+            Tracking::LockOn => {
+                // Keep looking at the object; orbit input still rotates around it
+                if self.mode != CameraMode::Orbit {
+                    self.enter_orbit();
+                }
+                self.target = position;
+                self.sync_orbit();
+            }
+        }
+        self.tracked_position = Some(position);
+    }
+
+    /// Recompute yaw, pitch and distance from the current eye and target
+    fn sync_orbit(&mut self) {
+        let direction = self.eye - self.target;
+        self.distance = direction.length().max(1e-3);
+        self.pitch = (direction.y / self.distance).clamp(-1.0, 1.0).asin();
+        self.yaw = direction.z.atan2(direction.x);
+    }
+
+    /// Drop any motion left over from previous input
+    fn stop(&mut self) {
+        self.yaw_velocity = 0.0;
+        self.pitch_velocity = 0.0;
+        self.zoom_velocity = 0.0;
+        self.pan_velocity = Vector2::zero();
+        self.fly_velocity = Vector3::zero();
+        self.roll_velocity = 0.0;
+        self.chase_velocity = Vector3::zero();
+    }
+
+    /// Move the camera to `eye`. The orbit camera also takes the new `target`;
+    /// the free-fly and chase cameras keep their orientation, and the cockpit stays inside the ship.
+    pub fn set_position(&mut self, eye: Vector3, target: Vector3) {
+        match self.mode {
+            CameraMode::Orbit => {
+                self.eye = eye;
+                self.target = target;
+            }
+            CameraMode::FreeFly => {
+                self.eye = eye;
+                self.update_free_fly_view();
+            }
+            CameraMode::Chase => self.eye = eye,
+            CameraMode::Cockpit => {}
         }
     }
 
+    /// Forward direction of the free-fly camera in world space
+    fn forward(&self) -> Vector3 {
+        Vector3::new(0.0, 0.0, -1.0).rotate_by(self.orientation)
+    }
+
+    /// Derive `target` and `up` from the free-fly orientation, so `get_view_matrix` works for both modes
+    fn update_free_fly_view(&mut self) {
+        self.target = self.eye + self.forward() * self.distance;
+        self.up = Vector3::new(0.0, 1.0, 0.0).rotate_by(self.orientation);
+    }
+
     /// Update camera eye position based on yaw, pitch, and distance
     fn update_eye_position(&mut self) {
         // Clamp pitch to avoid gimbal lock
@@ -67,39 +309,184 @@ impl Camera {
         create_view_matrix(self.eye, self.target, self.up)
     }
 
-    /// Process keyboard input to control the camera
-    pub fn process_input(&mut self, window: &RaylibHandle) {
-        // Rotation controls (yaw)
-        if window.is_key_down(KeyboardKey::KEY_A) {
-            self.yaw += self.rotation_speed;
-            self.update_eye_position();
+    /// Process keyboard input to control the camera.
+    /// Keys set a target velocity; the actual velocity eases towards it and the
+    /// motion is integrated with `dt`, so the camera feels the same at any frame rate.
+    pub fn process_input(&mut self, window: &RaylibHandle, bindings: &Bindings, dt: f32) {
+        match self.mode {
+            CameraMode::Orbit => {}
+            CameraMode::FreeFly => return self.process_free_fly_input(window, bindings, dt),
+            CameraMode::Chase | CameraMode::Cockpit => return, // Placed by `attach_to_ship`
         }
-        if window.is_key_down(KeyboardKey::KEY_D) {
-            self.yaw -= self.rotation_speed;
-            self.update_eye_position();
+
+        let yaw_input = bindings.axis(window, Action::YawLeft, Action::YawRight);
+        let pitch_input = bindings.axis(window, Action::PitchUp, Action::PitchDown);
+        let zoom_input = bindings.axis(window, Action::ZoomOut, Action::ZoomIn);
+        let pan_right_input = bindings.axis(window, Action::PanLeft, Action::PanRight);
+        let pan_up_input = bindings.axis(window, Action::PanUp, Action::PanDown);
+
+        self.yaw_velocity = self.smooth(self.yaw_velocity, yaw_input * self.rotation_speed, dt);
+        self.pitch_velocity = self.smooth(self.pitch_velocity, pitch_input * self.rotation_speed, dt);
+        self.zoom_velocity = self.smooth(self.zoom_velocity, zoom_input * self.zoom_speed, dt);
+        self.pan_velocity.x = self.smooth(self.pan_velocity.x, pan_right_input * self.pan_speed, dt);
+        self.pan_velocity.y = self.smooth(self.pan_velocity.y, pan_up_input * self.pan_speed, dt);
+
+        self.apply_velocity(dt);
+    }
+
+    /// Process mouse input for the orbit camera:
+    /// left drag rotates, middle drag (or shift + left drag) pans the target,
+    /// and the wheel zooms towards the point under the cursor.
+    /// `viewport` is the part of the screen this camera renders into.
+    pub fn process_mouse(&mut self, window: &RaylibHandle, bindings: &Bindings, viewport: Viewport) {
+        match self.mode {
+            CameraMode::Orbit => {}
+            CameraMode::FreeFly => return self.process_free_fly_mouse(window, bindings),
+            CameraMode::Chase | CameraMode::Cockpit => return,
         }
 
-        // Rotation controls (pitch)
-        if window.is_key_down(KeyboardKey::KEY_W) {
-            self.pitch += self.rotation_speed;
+        let delta = window.get_mouse_delta();
+        let shift = bindings.is_down(window, Action::PanModifier);
+        let left = bindings.is_down(window, Action::OrbitDrag);
+        let middle = bindings.is_down(window, Action::PanDrag);
+
+        let width = viewport.width as f32;
+        let height = viewport.height as f32;
+
+        if (middle || (left && shift)) && (delta.x != 0.0 || delta.y != 0.0) {
+            // Move the target so the scene follows the cursor at the target's depth
+            let (_, right, up) = self.basis();
+            let units_per_pixel = 2.0 * self.distance * (self.fov / 2.0).tan() / height;
+            self.target = self.target - right * (delta.x * units_per_pixel) + up * (delta.y * units_per_pixel);
             self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_S) {
-            self.pitch -= self.rotation_speed;
+        } else if left && (delta.x != 0.0 || delta.y != 0.0) {
+            self.yaw -= delta.x * self.mouse_sensitivity;
+            self.pitch += delta.y * self.mouse_sensitivity;
             self.update_eye_position();
         }
 
-        // Zoom controls (distance from target) - arrow keys
-        if window.is_key_down(KeyboardKey::KEY_UP) {
-            self.distance -= self.zoom_speed;
-            if self.distance < 0.5 {
-                self.distance = 0.5; // Prevent camera from going too close
+        let wheel = window.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            let cursor = viewport.to_local(window.get_mouse_position());
+            self.zoom_to_cursor(wheel, cursor, width, height);
+        }
+    }
+
+    /// Keyboard controls of the free-fly camera. By default W/S thrust, A/D strafe, R/F vertical,
+    /// Q/E roll and the arrow keys to pitch and yaw
+    fn process_free_fly_input(&mut self, window: &RaylibHandle, bindings: &Bindings, dt: f32) {
+        let thrust = Vector3::new(
+            bindings.axis(window, Action::FlyRight, Action::FlyLeft),
+            bindings.axis(window, Action::FlyUp, Action::FlyDown),
+            bindings.axis(window, Action::FlyForward, Action::FlyBackward),
+        ) * self.thrust_speed;
+        let yaw_input = bindings.axis(window, Action::LookLeft, Action::LookRight);
+        let pitch_input = bindings.axis(window, Action::LookUp, Action::LookDown);
+        let roll_input = bindings.axis(window, Action::RollLeft, Action::RollRight);
+
+        self.fly_velocity.x = self.smooth(self.fly_velocity.x, thrust.x, dt);
+        self.fly_velocity.y = self.smooth(self.fly_velocity.y, thrust.y, dt);
+        self.fly_velocity.z = self.smooth(self.fly_velocity.z, thrust.z, dt);
+        self.yaw_velocity = self.smooth(self.yaw_velocity, yaw_input * self.rotation_speed, dt);
+        self.pitch_velocity = self.smooth(self.pitch_velocity, pitch_input * self.rotation_speed, dt);
+        self.roll_velocity = self.smooth(self.roll_velocity, roll_input * self.roll_speed, dt);
+
+        self.rotate_local(self.pitch_velocity * dt, self.yaw_velocity * dt, self.roll_velocity * dt);
+
+        // Thrust is given in camera space
+        let right = Vector3::new(1.0, 0.0, 0.0).rotate_by(self.orientation);
+        let up = Vector3::new(0.0, 1.0, 0.0).rotate_by(self.orientation);
+        let velocity = right * self.fly_velocity.x + up * self.fly_velocity.y + self.forward() * self.fly_velocity.z;
+        self.eye += velocity * dt;
+
+        self.update_free_fly_view();
+    }
+
+    /// Mouse-look for the free-fly camera: left drag turns the view, the wheel changes the thrust speed
+    fn process_free_fly_mouse(&mut self, window: &RaylibHandle, bindings: &Bindings) {
+        if bindings.is_down(window, Action::LookDrag) {
+            let delta = window.get_mouse_delta();
+            if delta.x != 0.0 || delta.y != 0.0 {
+                self.rotate_local(-delta.y * self.mouse_sensitivity, -delta.x * self.mouse_sensitivity, 0.0);
+                self.update_free_fly_view();
             }
-            self.update_eye_position();
         }
-        if window.is_key_down(KeyboardKey::KEY_DOWN) {
-            self.distance += self.zoom_speed;
-            self.update_eye_position();
+
+        let wheel = window.get_mouse_wheel_move();
+        if wheel != 0.0 {
+            self.thrust_speed = (self.thrust_speed * 1.25_f32.powf(wheel)).clamp(1.0, 500.0);
+        }
+    }
+
+    /// Rotate the free-fly camera around its own axes (pitch: right, yaw: up, roll: backwards)
+    fn rotate_local(&mut self, pitch: f32, yaw: f32, roll: f32) {
+        if pitch == 0.0 && yaw == 0.0 && roll == 0.0 {
+            return;
+        }
+        let delta = Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), pitch)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), yaw)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), roll);
+        // Renormalize so rounding errors don't accumulate into a scaled rotation
+        self.orientation = (self.orientation * delta).normalized();
+    }
+
+    /// Zooms by `notches` wheel steps while keeping the point under `cursor` fixed on screen
+    fn zoom_to_cursor(&mut self, notches: f32, cursor: Vector2, width: f32, height: f32) {
+        let (forward, right, up) = self.basis();
+
+        // Direction of the ray through the cursor, scaled so its forward component is 1
+        let tan_half_fov = (self.fov / 2.0).tan();
+        let ndc_x = 2.0 * cursor.x / width - 1.0;
+        let ndc_y = 1.0 - 2.0 * cursor.y / height;
+        let ray = forward + right * (ndc_x * tan_half_fov * width / height) + up * (ndc_y * tan_half_fov);
+
+        // Point under the cursor on the plane through the target
+        let depth = (self.target - self.eye).dot(forward);
+        let cursor_point = self.eye + ray * depth;
+
+        let new_distance = (self.distance * (1.0 - self.wheel_zoom_step).powf(notches)).max(0.5);
+        let factor = new_distance / self.distance;
+
+        self.target = self.target + (cursor_point - self.target) * (1.0 - factor);
+        self.distance = new_distance;
+        self.update_eye_position();
+    }
+
+    /// Forward, right and up unit vectors of the current view
+    fn basis(&self) -> (Vector3, Vector3, Vector3) {
+        let forward = (self.target - self.eye).normalized();
+        let right = forward.cross(self.up).normalized();
+        let up = right.cross(forward);
+        (forward, right, up)
+    }
+
+    /// Eases `velocity` towards `target`: `acceleration` while a key is held, `damping` once released
+    fn smooth(&self, velocity: f32, target: f32, dt: f32) -> f32 {
+        let rate = if target != 0.0 { self.acceleration } else { self.damping };
+        let next = velocity + (target - velocity) * (1.0 - (-rate * dt).exp());
+        if target == 0.0 && next.abs() < 1e-4 { 0.0 } else { next }
+    }
+
+    /// Integrate the current velocities over `dt`
+    fn apply_velocity(&mut self, dt: f32) {
+        let moving = self.yaw_velocity != 0.0
+            || self.pitch_velocity != 0.0
+            || self.zoom_velocity != 0.0
+            || self.pan_velocity.x != 0.0
+            || self.pan_velocity.y != 0.0;
+        if !moving {
+            return;
+        }
+
+        // Rotation controls (yaw and pitch)
+        self.yaw += self.yaw_velocity * dt;
+        self.pitch += self.pitch_velocity * dt;
+
+        // Zoom controls (distance from target)
+        self.distance += self.zoom_velocity * dt;
+        if self.distance < 0.5 {
+            self.distance = 0.5; // Prevent camera from going too close
+            self.zoom_velocity = 0.0;
         }
 
         // Pan controls (move target/center point)
@@ -122,38 +509,10 @@ impl Camera {
             -forward_normalized.x,
         );
 
-        // Q/E keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_Q) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_E) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-
-        // Left/Right arrow keys for horizontal panning
-        if window.is_key_down(KeyboardKey::KEY_LEFT) {
-            self.target.x += right.x * self.pan_speed;
-            self.target.z += right.z * self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_RIGHT) {
-            self.target.x -= right.x * self.pan_speed;
-            self.target.z -= right.z * self.pan_speed;
-            self.update_eye_position();
-        }
+        self.target.x += right.x * self.pan_velocity.x * dt;
+        self.target.z += right.z * self.pan_velocity.x * dt;
+        self.target.y += self.pan_velocity.y * dt;
 
-        // Vertical panning
-        if window.is_key_down(KeyboardKey::KEY_R) {
-            self.target.y += self.pan_speed;
-            self.update_eye_position();
-        }
-        if window.is_key_down(KeyboardKey::KEY_F) {
-            self.target.y -= self.pan_speed;
-            self.update_eye_position();
-        }
+        self.update_eye_position();
     }
-}
\ No newline at end of file
+}
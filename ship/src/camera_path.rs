@@ -0,0 +1,267 @@
+// camera_path.rs
+use raylib::prelude::*;
+use std::fs;
+use std::io;
+
+/// Camera state at a moment of the simulation
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub time: f32, // Simulation time
+    pub eye: Vector3,
+    pub target: Vector3,
+    pub up: Vector3,
+    pub fov: f32,
+}
+
+/// Keyframes ordered by simulation time, stored as plain text so they can be edited by hand
+#[derive(Clone, Debug, Default)]
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    /// Adds a keyframe, keeping the list sorted by time
+    pub fn push(&mut self, keyframe: Keyframe) {
+        let index = self.keyframes.partition_point(|existing| existing.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn start_time(&self) -> f32 {
+        self.keyframes.first().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    pub fn end_time(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// One keyframe per line: time, eye, target, up (three numbers each) and FOV in radians.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut text = String::from("# time  eye.x eye.y eye.z  target.x target.y target.z  up.x up.y up.z  fov\n");
+        for k in &self.keyframes {
+            text.push_str(&format!(
+                "{:.4}  {:.4} {:.4} {:.4}  {:.4} {:.4} {:.4}  {:.4} {:.4} {:.4}  {:.4}\n",
+                k.time, k.eye.x, k.eye.y, k.eye.z, k.target.x, k.target.y, k.target.z, k.up.x, k.up.y, k.up.z, k.fov,
+            ));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        CameraPath::parse(&fs::read_to_string(path)?, path)
+    }
+
+    /// Reads the text written by `save`; `source` names it in error messages
+    fn parse(text: &str, source: &str) -> io::Result<Self> {
+        let mut camera_path = CameraPath::default();
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("{source}:{}: {message}", line_number + 1));
+            let values = line
+                .split_whitespace()
+                .map(|value| value.parse::<f32>().map_err(|error| invalid(format!("{value:?}: {error}"))))
+                .collect::<io::Result<Vec<f32>>>()?;
+            if values.len() != 11 {
+                return Err(invalid(format!("expected 11 numbers, found {}", values.len())));
+            }
+
+            camera_path.push(Keyframe {
+                time: values[0],
+                eye: Vector3::new(values[1], values[2], values[3]),
+                target: Vector3::new(values[4], values[5], values[6]),
+                up: Vector3::new(values[7], values[8], values[9]),
+                fov: values[10],
+            });
+        }
+
+        Ok(camera_path)
+    }
+
+    /// Camera state at simulation `time`, interpolated with a Catmull-Rom spline through the keyframes
+    pub fn sample(&self, time: f32) -> Option<Keyframe> {
+        let keyframes = &self.keyframes;
+        let last = keyframes.len().checked_sub(1)?;
+        if last == 0 || time <= keyframes[0].time {
+            return Some(keyframes[0]);
+        }
+        if time >= keyframes[last].time {
+            return Some(keyframes[last]);
+        }
+
+        // Segment between keyframes i and i + 1, neighbours repeated at the ends
+        let i = keyframes.partition_point(|keyframe| keyframe.time <= time) - 1;
+        let p0 = keyframes[i.saturating_sub(1)];
+        let p1 = keyframes[i];
+        let p2 = keyframes[i + 1];
+        let p3 = keyframes[(i + 2).min(last)];
+
+        let span = p2.time - p1.time;
+        let t = if span > 0.0 { (time - p1.time) / span } else { 0.0 };
+        let spline = |a: Vector3, b: Vector3, c: Vector3, d: Vector3| catmull_rom(a, b, c, d, t);
+
+        let up = spline(p0.up, p1.up, p2.up, p3.up);
+        let fov = catmull_rom(
+            Vector3::new(p0.fov, 0.0, 0.0),
+            Vector3::new(p1.fov, 0.0, 0.0),
+            Vector3::new(p2.fov, 0.0, 0.0),
+            Vector3::new(p3.fov, 0.0, 0.0),
+            t,
+        ).x;
+
+        Some(Keyframe {
+            time,
+            eye: spline(p0.eye, p1.eye, p2.eye, p3.eye),
+            target: spline(p0.target, p1.target, p2.target, p3.target),
+            up: if up.length() > 1e-3 { up.normalized() } else { p1.up },
+            fov,
+        })
+    }
+}
+
+/// Plays a path back from its first keyframe
+pub struct Playback {
+    pub path: CameraPath,
+    elapsed: f32,
+}
+
+impl Playback {
+    pub fn new(path: CameraPath) -> Self {
+        Playback { path, elapsed: 0.0 }
+    }
+
+    /// Moves the playback forward by `dt` and returns the camera state, or `None` once the path is over
+    pub fn advance(&mut self, dt: f32) -> Option<Keyframe> {
+        let time = self.path.start_time() + self.elapsed;
+        if time > self.path.end_time() {
+            return None;
+        }
+        self.elapsed += dt;
+        self.path.sample(time)
+    }
+}
+
+/// Uniform Catmull-Rom spline between `p1` and `p2`
+fn catmull_rom(p0: Vector3, p1: Vector3, p2: Vector3, p3: Vector3, t: f32) -> Vector3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: Vector3, b: Vector3) -> bool {
+        a.distance_to(b) < 1e-4
+    }
+
+    fn keyframe(time: f32, eye: Vector3) -> Keyframe {
+        Keyframe { time, eye, target: Vector3::zero(), up: Vector3::new(0.0, 1.0, 0.0), fov: 1.0 }
+    }
+
+    fn straight_path() -> CameraPath {
+        let mut path = CameraPath::default();
+        for i in 0..4 {
+            path.push(keyframe(i as f32 * 2.0, Vector3::new(i as f32, 0.0, 5.0)));
+        }
+        path
+    }
+
+    #[test]
+    fn parse_skips_comments_and_sorts_by_time() {
+        let text = "# header\n\n2  1 2 3  0 0 0  0 1 0  0.8\n   \n0.5  4 5 6  0 0 0  0 1 0  1.2\n";
+        let path = CameraPath::parse(text, "test").unwrap();
+        assert_eq!(path.keyframes.len(), 2);
+        assert_eq!(path.start_time(), 0.5);
+        assert_eq!(path.end_time(), 2.0);
+        assert!(approx(path.keyframes[0].eye, Vector3::new(4.0, 5.0, 6.0)));
+        assert_eq!(path.keyframes[1].fov, 0.8);
+    }
+
+    #[test]
+    fn parse_reports_the_line_of_a_wrong_count() {
+        let error = CameraPath::parse("# header\n0 1 2 3 0 0 0 0 1 0\n", "path.txt").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().starts_with("path.txt:2:"));
+        assert!(error.to_string().contains("found 10"));
+    }
+
+    #[test]
+    fn parse_rejects_values_that_are_not_numbers() {
+        let error = CameraPath::parse("0 1 2 3 0 0 0 0 1 0 wide\n", "path.txt").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("\"wide\""));
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let file = std::env::temp_dir().join(format!("camera_path_test_{}.txt", std::process::id()));
+        let file = file.to_str().unwrap();
+        straight_path().save(file).unwrap();
+        let loaded = CameraPath::load(file);
+        fs::remove_file(file).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.keyframes.len(), 4);
+        for (a, b) in loaded.keyframes.iter().zip(&straight_path().keyframes) {
+            assert_eq!(a.time, b.time);
+            assert!(approx(a.eye, b.eye));
+        }
+    }
+
+    #[test]
+    fn sample_needs_keyframes() {
+        assert!(CameraPath::default().sample(0.0).is_none());
+
+        let mut single = CameraPath::default();
+        single.push(keyframe(3.0, Vector3::new(1.0, 2.0, 3.0)));
+        assert!(approx(single.sample(10.0).unwrap().eye, Vector3::new(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn sample_passes_through_the_keyframes() {
+        let path = straight_path();
+        for keyframe in &path.keyframes {
+            let sampled = path.sample(keyframe.time).unwrap();
+            assert!(approx(sampled.eye, keyframe.eye));
+            assert_eq!(sampled.fov, keyframe.fov);
+        }
+    }
+
+    #[test]
+    fn sample_clamps_at_the_ends() {
+        let path = straight_path();
+        assert!(approx(path.sample(-5.0).unwrap().eye, path.keyframes[0].eye));
+        assert!(approx(path.sample(100.0).unwrap().eye, path.keyframes[3].eye));
+    }
+
+    #[test]
+    fn sample_follows_evenly_spaced_points_in_a_line() {
+        // Between inner keyframes, Catmull-Rom through equally spaced collinear points moves at constant speed
+        let path = straight_path();
+        assert!(approx(path.sample(3.0).unwrap().eye, Vector3::new(1.5, 0.0, 5.0)));
+        assert!(approx(path.sample(3.5).unwrap().eye, Vector3::new(1.75, 0.0, 5.0)));
+
+        // On the end segments the repeated keyframe eases the motion in and out
+        assert!(approx(path.sample(1.0).unwrap().eye, Vector3::new(0.4375, 0.0, 5.0)));
+        assert!(approx(path.sample(5.0).unwrap().eye, Vector3::new(2.5625, 0.0, 5.0)));
+    }
+
+    #[test]
+    fn playback_stops_after_the_last_keyframe() {
+        let mut playback = Playback::new(straight_path());
+        assert!(approx(playback.advance(4.0).unwrap().eye, Vector3::new(0.0, 0.0, 5.0)));
+        assert!(approx(playback.advance(2.0).unwrap().eye, Vector3::new(2.0, 0.0, 5.0)));
+        assert!(approx(playback.advance(2.0).unwrap().eye, Vector3::new(3.0, 0.0, 5.0)));
+        assert!(playback.advance(2.0).is_none());
+    }
+}
@@ -0,0 +1,322 @@
+// collision.rs
+#![allow(dead_code)]
+
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+
+/// Shape used to detect contacts, given in model space
+#[derive(Clone)]
+pub enum Collider {
+    Sphere { center: Vector3, radius: f32 },
+    Capsule { start: Vector3, end: Vector3, radius: f32 },
+    Mesh(MeshCollider),
+}
+
+/// Triangle soup with a bounding sphere to skip far away obstacles quickly
+#[derive(Clone)]
+pub struct MeshCollider {
+    pub triangles: Vec<[Vector3; 3]>,
+    pub bounds_center: Vector3,
+    pub bounds_radius: f32,
+}
+
+impl MeshCollider {
+    /// Builds the collider from a triangle list, like the one returned by `Obj::get_vertex_array`
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        let triangles: Vec<[Vector3; 3]> = vertices
+            .chunks_exact(3)
+            .map(|tri| [tri[0].position, tri[1].position, tri[2].position])
+            .collect();
+
+        // Center of the bounding box, radius reaching the farthest vertex
+        let mut min = Vector3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+        let mut max = Vector3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for point in triangles.iter().flatten() {
+            min = Vector3::new(min.x.min(point.x), min.y.min(point.y), min.z.min(point.z));
+            max = Vector3::new(max.x.max(point.x), max.y.max(point.y), max.z.max(point.z));
+        }
+        let bounds_center = if triangles.is_empty() { Vector3::zero() } else { (min + max) * 0.5 };
+        let bounds_radius = triangles
+            .iter()
+            .flatten()
+            .map(|point| point.distance_to(bounds_center))
+            .fold(0.0, f32::max);
+
+        MeshCollider { triangles, bounds_center, bounds_radius }
+    }
+}
+
+/// A detected overlap with a spherical obstacle
+#[derive(Clone, Copy, Debug)]
+pub struct Contact {
+    pub normal: Vector3, // Unit vector pointing away from the obstacle, the way out for the collider
+    pub depth: f32,      // How far the collider has to move along `normal` to stop overlapping
+    pub point: Vector3,  // Point of the collider deepest inside the obstacle
+}
+
+/// What happens to a body's velocity when it touches an obstacle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Response {
+    Stop,                       // Come to rest against the obstacle
+    Bounce { restitution: f32 }, // Reflect the approaching velocity, 1 keeps all the energy
+    Slide { friction: f32 },     // Drop the approaching velocity and keep moving along the surface
+}
+
+impl Collider {
+    /// Contact between this collider, placed in the world by `transform`, and a sphere
+    pub fn contact_with_sphere(&self, transform: Matrix, center: Vector3, radius: f32) -> Option<Contact> {
+        // Colliders use uniform scale, so any axis gives the scale factor
+        let scale = (Vector3::new(1.0, 0.0, 0.0).transform_with(transform) - Vector3::zero().transform_with(transform)).length();
+
+        match self {
+            Collider::Sphere { center: local_center, radius: local_radius } => {
+                sphere_sphere(local_center.transform_with(transform), local_radius * scale, center, radius)
+            }
+            Collider::Capsule { start, end, radius: local_radius } => capsule_sphere(
+                start.transform_with(transform),
+                end.transform_with(transform),
+                local_radius * scale,
+                center,
+                radius,
+            ),
+            Collider::Mesh(mesh) => {
+                let bounds_center = mesh.bounds_center.transform_with(transform);
+                if bounds_center.distance_to(center) > mesh.bounds_radius * scale + radius {
+                    return None;
+                }
+                let triangles: Vec<[Vector3; 3]> = mesh
+                    .triangles
+                    .iter()
+                    .map(|tri| tri.map(|point| point.transform_with(transform)))
+                    .collect();
+                mesh_sphere(&triangles, center, radius)
+            }
+        }
+    }
+}
+
+/// Contact of sphere A against obstacle sphere B
+pub fn sphere_sphere(center_a: Vector3, radius_a: f32, center_b: Vector3, radius_b: f32) -> Option<Contact> {
+    let offset = center_a - center_b;
+    let distance = offset.length();
+    let depth = radius_a + radius_b - distance;
+    if depth <= 0.0 {
+        return None;
+    }
+
+    // Concentric spheres have no preferred direction, push up
+    let normal = if distance > 1e-6 { offset / distance } else { Vector3::new(0.0, 1.0, 0.0) };
+    Some(Contact { normal, depth, point: center_a - normal * radius_a })
+}
+
+/// Contact of a capsule against an obstacle sphere
+pub fn capsule_sphere(start: Vector3, end: Vector3, radius: f32, center: Vector3, obstacle_radius: f32) -> Option<Contact> {
+    let closest = closest_point_on_segment(center, start, end);
+    sphere_sphere(closest, radius, center, obstacle_radius)
+}
+
+/// Contact of a triangle mesh (in world space) against an obstacle sphere, using the deepest triangle
+pub fn mesh_sphere(triangles: &[[Vector3; 3]], center: Vector3, radius: f32) -> Option<Contact> {
+    let mut deepest: Option<Contact> = None;
+    for [a, b, c] in triangles {
+        let closest = closest_point_on_triangle(center, *a, *b, *c);
+        let offset = closest - center;
+        let distance = offset.length();
+        let depth = radius - distance;
+        if depth <= 0.0 || deepest.is_some_and(|contact| contact.depth >= depth) {
+            continue;
+        }
+
+        let normal = if distance > 1e-6 { offset / distance } else { Vector3::new(0.0, 1.0, 0.0) };
+        deepest = Some(Contact { normal, depth, point: closest });
+    }
+    deepest
+}
+
+/// Moves a body out of an obstacle and changes its velocity according to `response`.
+/// `obstacle_velocity` lets moving planets push the body instead of swallowing it.
+pub fn resolve(position: &mut Vector3, velocity: &mut Vector3, obstacle_velocity: Vector3, contact: &Contact, response: Response) {
+    *position += contact.normal * contact.depth;
+
+    let relative = *velocity - obstacle_velocity;
+    let approaching = relative.dot(contact.normal);
+    if approaching >= 0.0 {
+        return; // Already separating
+    }
+
+    let normal_velocity = contact.normal * approaching;
+    let tangent_velocity = relative - normal_velocity;
+    let relative = match response {
+        Response::Stop => Vector3::zero(),
+        Response::Bounce { restitution } => tangent_velocity - normal_velocity * restitution,
+        Response::Slide { friction } => tangent_velocity * (1.0 - friction.clamp(0.0, 1.0)),
+    };
+    *velocity = obstacle_velocity + relative;
+}
+
+pub fn closest_point_on_segment(point: Vector3, start: Vector3, end: Vector3) -> Vector3 {
+    let segment = end - start;
+    let length_squared = segment.dot(segment);
+    if length_squared <= 1e-12 {
+        return start;
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    start + segment * t
+}
+
+/// Closest point to `point` on the triangle abc, by testing which Voronoi region it falls in
+pub fn closest_point_on_triangle(point: Vector3, a: Vector3, b: Vector3, c: Vector3) -> Vector3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = point - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = point - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = point - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    // Inside the face
+    let denominator = 1.0 / (va + vb + vc);
+    a + ab * (vb * denominator) + ac * (vc * denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: Vector3, b: Vector3) -> bool {
+        a.distance_to(b) < 1e-4
+    }
+
+    fn square_mesh() -> MeshCollider {
+        // Unit square on the XZ plane, facing +Y
+        let vertex = |x: f32, z: f32| Vertex::new(Vector3::new(x, 0.0, z), Vector3::new(0.0, 1.0, 0.0), Vector2::zero());
+        MeshCollider::from_vertices(&[
+            vertex(-1.0, -1.0), vertex(1.0, -1.0), vertex(1.0, 1.0),
+            vertex(-1.0, -1.0), vertex(1.0, 1.0), vertex(-1.0, 1.0),
+        ])
+    }
+
+    #[test]
+    fn separate_spheres_have_no_contact() {
+        assert!(sphere_sphere(Vector3::new(3.0, 0.0, 0.0), 1.0, Vector3::zero(), 1.5).is_none());
+    }
+
+    #[test]
+    fn overlapping_spheres_push_apart() {
+        let contact = sphere_sphere(Vector3::new(2.0, 0.0, 0.0), 1.0, Vector3::zero(), 1.5).unwrap();
+        assert!(approx(contact.normal, Vector3::new(1.0, 0.0, 0.0)));
+        assert!((contact.depth - 0.5).abs() < 1e-5);
+        assert!(approx(contact.point, Vector3::new(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn capsule_uses_closest_point_of_its_axis() {
+        let start = Vector3::new(-5.0, 1.2, 0.0);
+        let end = Vector3::new(5.0, 1.2, 0.0);
+        let contact = capsule_sphere(start, end, 0.5, Vector3::zero(), 1.0).unwrap();
+        assert!(approx(contact.normal, Vector3::new(0.0, 1.0, 0.0)));
+        assert!((contact.depth - 0.3).abs() < 1e-5);
+
+        // Past the end cap the capsule behaves like a sphere
+        assert!(capsule_sphere(start, end, 0.5, Vector3::new(7.0, 1.2, 0.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn closest_point_on_triangle_regions() {
+        let (a, b, c) = (Vector3::zero(), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        // Vertex, edge and face regions
+        assert!(approx(closest_point_on_triangle(Vector3::new(-1.0, 0.0, -1.0), a, b, c), a));
+        assert!(approx(closest_point_on_triangle(Vector3::new(0.5, 2.0, -1.0), a, b, c), Vector3::new(0.5, 0.0, 0.0)));
+        assert!(approx(closest_point_on_triangle(Vector3::new(1.0, 0.0, 1.0), a, b, c), Vector3::new(0.5, 0.0, 0.5)));
+        assert!(approx(closest_point_on_triangle(Vector3::new(0.2, 3.0, 0.2), a, b, c), Vector3::new(0.2, 0.0, 0.2)));
+    }
+
+    #[test]
+    fn mesh_contact_against_sphere_below() {
+        let mesh = square_mesh();
+        let collider = Collider::Mesh(mesh);
+
+        let contact = collider
+            .contact_with_sphere(Matrix::identity(), Vector3::new(0.3, -0.8, 0.0), 1.0)
+            .unwrap();
+        assert!(approx(contact.normal, Vector3::new(0.0, 1.0, 0.0)));
+        assert!((contact.depth - 0.2).abs() < 1e-5);
+
+        // Far away obstacles are rejected by the bounding sphere
+        assert!(collider.contact_with_sphere(Matrix::identity(), Vector3::new(0.0, -5.0, 0.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn mesh_collider_follows_its_transform() {
+        let collider = Collider::Mesh(square_mesh());
+        let transform = Matrix::translate(0.0, 10.0, 0.0);
+        assert!(collider.contact_with_sphere(transform, Vector3::new(0.0, -0.8, 0.0), 1.0).is_none());
+        assert!(collider.contact_with_sphere(transform, Vector3::new(0.0, 9.2, 0.0), 1.0).is_some());
+    }
+
+    #[test]
+    fn responses_change_the_approaching_velocity() {
+        let contact = Contact { normal: Vector3::new(0.0, 1.0, 0.0), depth: 0.25, point: Vector3::zero() };
+        let incoming = Vector3::new(2.0, -3.0, 0.0);
+
+        let (mut position, mut velocity) = (Vector3::zero(), incoming);
+        resolve(&mut position, &mut velocity, Vector3::zero(), &contact, Response::Stop);
+        assert!(approx(position, Vector3::new(0.0, 0.25, 0.0)));
+        assert!(approx(velocity, Vector3::zero()));
+
+        let (mut position, mut velocity) = (Vector3::zero(), incoming);
+        resolve(&mut position, &mut velocity, Vector3::zero(), &contact, Response::Bounce { restitution: 0.5 });
+        assert!(approx(velocity, Vector3::new(2.0, 1.5, 0.0)));
+
+        let (mut position, mut velocity) = (Vector3::zero(), incoming);
+        resolve(&mut position, &mut velocity, Vector3::zero(), &contact, Response::Slide { friction: 0.25 });
+        assert!(approx(velocity, Vector3::new(1.5, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn separating_bodies_keep_their_velocity() {
+        let contact = Contact { normal: Vector3::new(0.0, 1.0, 0.0), depth: 0.1, point: Vector3::zero() };
+        let (mut position, mut velocity) = (Vector3::zero(), Vector3::new(0.0, 2.0, 0.0));
+        resolve(&mut position, &mut velocity, Vector3::zero(), &contact, Response::Bounce { restitution: 1.0 });
+        assert!(approx(velocity, Vector3::new(0.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn moving_obstacle_carries_a_stopped_body() {
+        let contact = Contact { normal: Vector3::new(1.0, 0.0, 0.0), depth: 0.1, point: Vector3::zero() };
+        let obstacle_velocity = Vector3::new(4.0, 0.0, 1.0);
+        let (mut position, mut velocity) = (Vector3::zero(), Vector3::zero());
+        resolve(&mut position, &mut velocity, obstacle_velocity, &contact, Response::Stop);
+        assert!(approx(velocity, obstacle_velocity));
+    }
+}
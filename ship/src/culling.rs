@@ -0,0 +1,117 @@
+// culling.rs
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+
+/// Bounding volumes of a mesh in model space: an axis-aligned box and a sphere around its center
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub min: Vector3,
+    pub max: Vector3,
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+impl Bounds {
+    pub fn from_vertices(vertices: &[Vertex]) -> Self {
+        let Some(first) = vertices.first() else {
+            return Bounds { min: Vector3::zero(), max: Vector3::zero(), center: Vector3::zero(), radius: 0.0 };
+        };
+
+        let (min, max) = vertices.iter().fold((first.position, first.position), |(min, max), vertex| {
+            (min.min(vertex.position), max.max(vertex.position))
+        });
+        let center = (min + max) * 0.5;
+        let radius = vertices
+            .iter()
+            .map(|vertex| vertex.position.distance_to(center))
+            .fold(0.0, f32::max);
+
+        Bounds { min, max, center, radius }
+    }
+
+    fn corners(&self) -> [Vector3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vector3::new(min.x, min.y, min.z),
+            Vector3::new(max.x, min.y, min.z),
+            Vector3::new(min.x, max.y, min.z),
+            Vector3::new(max.x, max.y, min.z),
+            Vector3::new(min.x, min.y, max.z),
+            Vector3::new(max.x, min.y, max.z),
+            Vector3::new(min.x, max.y, max.z),
+            Vector3::new(max.x, max.y, max.z),
+        ]
+    }
+}
+
+/// Planes of the view volume in world space, as (normal, distance) with the normal pointing inside
+pub struct Frustum {
+    planes: [(Vector3, f32); 5],
+}
+
+impl Frustum {
+    /// Extracts the planes from the rows of the combined matrix (Gribb–Hartmann).
+    /// The far plane is left out: the rasterizer doesn't clip against it, so distant bodies are still drawn.
+    pub fn new(view_matrix: Matrix, projection_matrix: Matrix) -> Self {
+        // `view * projection` applies the view first, like the vertex shader
+        let m = view_matrix * projection_matrix;
+        // Column-major storage: row i is made of elements i, i + 4, i + 8 and i + 12
+        let m = [m.m0, m.m1, m.m2, m.m3, m.m4, m.m5, m.m6, m.m7, m.m8, m.m9, m.m10, m.m11, m.m12, m.m13, m.m14, m.m15];
+        let row = |i: usize| Vector4::new(m[i], m[i + 4], m[i + 8], m[i + 12]);
+        let w = row(3);
+
+        // Each plane is the last row plus or minus one of the others, normalized so distances are in world units
+        let plane = |i: usize, sign: f32| {
+            let r = row(i);
+            let normal = Vector3::new(w.x + sign * r.x, w.y + sign * r.y, w.z + sign * r.z);
+            let length = normal.length();
+            (normal / length, (w.w + sign * r.w) / length)
+        };
+        Frustum {
+            planes: [
+                plane(0, 1.0),  // Left
+                plane(0, -1.0), // Right
+                plane(1, 1.0),  // Bottom
+                plane(1, -1.0), // Top
+                plane(2, 1.0),  // Near
+            ],
+        }
+    }
+
+    /// Whether a mesh with `bounds`, placed by `model_matrix`, may be visible.
+    /// The sphere is tested first; if it straddles a plane, the corners of the transformed box decide.
+    pub fn intersects(&self, bounds: &Bounds, model_matrix: Matrix) -> bool {
+        let center = bounds.center.transform_with(model_matrix);
+        let origin = Vector3::zero().transform_with(model_matrix);
+        let scale = [Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)]
+            .iter()
+            .map(|axis| (axis.transform_with(model_matrix) - origin).length())
+            .fold(0.0, f32::max);
+        let radius = bounds.radius * scale;
+
+        let mut straddling = false;
+        for &(normal, distance) in &self.planes {
+            let signed = normal.dot(center) + distance;
+            if signed < -radius {
+                return false;
+            }
+            straddling |= signed < radius;
+        }
+        if !straddling {
+            return true;
+        }
+
+        // Outside if all eight corners are behind the same plane
+        let corners = bounds.corners().map(|corner| corner.transform_with(model_matrix));
+        self.planes
+            .iter()
+            .all(|&(normal, distance)| corners.iter().any(|corner| normal.dot(*corner) + distance >= 0.0))
+    }
+}
+
+/// Meshes drawn and skipped by culling in one frame, over all views
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CullStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
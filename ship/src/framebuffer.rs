@@ -1,5 +1,13 @@
 use raylib::prelude::*;
 
+/// How a transparent fragment is combined with the color already in the buffer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlendMode {
+    Alpha,          // src * a + dst * (1 - a)
+    Additive,       // dst + src * a
+    Premultiplied,  // src + dst * (1 - a), src already multiplied by a
+}
+
 pub struct Framebuffer {
     pub width: i32,
     pub height: i32,
@@ -7,6 +15,9 @@ pub struct Framebuffer {
     background_color: Color,
     current_color: Color,
     depth_buffer: Vec<f32>,
+    selection_mask: Vec<bool>,                      // Píxeles cubiertos por el objeto seleccionado
+    selection_bounds: Option<(i32, i32, i32, i32)>, // Rectángulo (min_x, min_y, max_x, max_y) de la máscara
+    scissor: Option<(i32, i32, i32, i32)>,          // Rectángulo (x, y, ancho, alto) fuera del cual no se dibuja
 }
 
 impl Framebuffer {
@@ -14,6 +25,7 @@ impl Framebuffer {
         let background_color = Color::BLACK; // Un color por defecto
         let color_buffer = Image::gen_image_color(width, height, background_color);
         let depth_buffer = vec![f32::INFINITY; (width * height) as usize];
+        let selection_mask = vec![false; (width * height) as usize];
         Framebuffer {
             width,
             height,
@@ -21,16 +33,35 @@ impl Framebuffer {
             background_color,
             current_color: Color::WHITE,
             depth_buffer,
+            selection_mask,
+            selection_bounds: None,
+            scissor: None,
         }
     }
 
     pub fn clear(&mut self) {
         self.color_buffer.clear_background(self.background_color);
         self.depth_buffer.fill(f32::INFINITY);
+        self.selection_mask.fill(false);
+        self.selection_bounds = None;
+        self.scissor = None;
+    }
+
+    // Limita todo lo que se dibuje después a un rectángulo (x, y, ancho, alto); None lo quita
+    pub fn set_scissor(&mut self, scissor: Option<(i32, i32, i32, i32)>) {
+        self.scissor = scissor;
+    }
+
+    // El píxel está dentro de la pantalla y del rectángulo de recorte
+    fn inside(&self, x: i32, y: i32) -> bool {
+        let on_screen = x >= 0 && x < self.width && y >= 0 && y < self.height;
+        on_screen && self.scissor.is_none_or(|(left, top, width, height)| {
+            x >= left && x < left + width && y >= top && y < top + height
+        })
     }
     
     pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
+        if self.inside(x, y) {
             let index = (y * self.width + x) as usize;
 
             if depth < self.depth_buffer[index] {
@@ -46,46 +77,150 @@ impl Framebuffer {
         }
     }
     
-    // Método para dibujar una línea con profundidad específica
-    pub fn draw_line_with_depth(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color, depth: f32) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let x1 = x1;
-        let y1 = y1;
-        
-        let dx = (x1 - x0).abs();
-        let dy = (y1 - y0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx - dy;
-        
-        loop {
-            // Convertir el color de raylib a Vector3 para usar en point
-            let color_vec3 = Vector3::new(
-                color.r as f32 / 255.0,
-                color.g as f32 / 255.0,
-                color.b as f32 / 255.0
-            );
-            
-            // Usar point con la profundidad especificada
-            self.point(x0, y0, color_vec3, depth);
-            
-            if x0 == x1 && y0 == y1 {
-                break;
+    // Mezcla el color con el píxel existente según el modo indicado (para superficies transparentes).
+    // Respeta la profundidad pero no la escribe, así no tapa lo que está detrás.
+    pub fn blend_point(&mut self, x: i32, y: i32, color: Vector3, alpha: f32, depth: f32, mode: BlendMode) {
+        if self.inside(x, y) {
+            let index = (y * self.width + x) as usize;
+
+            if depth < self.depth_buffer[index] {
+                let alpha = alpha.clamp(0.0, 1.0);
+                let current = self.color_buffer.get_color(x, y);
+                let dst = Vector3::new(
+                    current.r as f32 / 255.0,
+                    current.g as f32 / 255.0,
+                    current.b as f32 / 255.0,
+                );
+
+                let blended = match mode {
+                    BlendMode::Alpha => color * alpha + dst * (1.0 - alpha),
+                    BlendMode::Additive => dst + color * alpha,
+                    BlendMode::Premultiplied => color + dst * (1.0 - alpha),
+                };
+
+                let pixel_color = Color::new(
+                    (blended.x.clamp(0.0, 1.0) * 255.0) as u8,
+                    (blended.y.clamp(0.0, 1.0) * 255.0) as u8,
+                    (blended.z.clamp(0.0, 1.0) * 255.0) as u8,
+                    255,
+                );
+                self.color_buffer.draw_pixel(x, y, pixel_color);
+            }
+        }
+    }
+
+    // Dibuja una línea con antialiasing de cualquier grosor. Recorre el eje mayor columna por columna y en cada una
+    // cubre una franja vertical del grosor de la línea; cada píxel toma como cobertura el área que la franja ocupa en él
+    // (la parte de la franja en su fila por la parte de la columna dentro de la línea, para los extremos).
+    // start y end están en pantalla (x, y) con la profundidad en z, que se interpola por píxel.
+    // La cobertura de cada píxel se usa como alpha, así que la línea se mezcla sin escribir profundidad.
+    pub fn draw_line_aa(&mut self, start: Vector3, end: Vector3, color: Vector3, alpha: f32, width: f32) {
+        // Recorrer siempre el eje mayor para que no queden huecos
+        let steep = (end.y - start.y).abs() > (end.x - start.x).abs();
+        let swap_axes = |v: Vector3| if steep { Vector3::new(v.y, v.x, v.z) } else { v };
+        let (mut a, mut b) = (swap_axes(start), swap_axes(end));
+        if a.x > b.x {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        let dx = b.x - a.x;
+        if dx <= 0.0 {
+            return;
+        }
+        let gradient = (b.y - a.y) / dx;
+        let depth_gradient = (b.z - a.z) / dx;
+
+        // Grosor medido sobre el eje menor
+        let half_thickness = width.max(1.0) * 0.5 * (1.0 + gradient * gradient).sqrt();
+
+        for column in a.x.floor() as i32..=b.x.floor() as i32 {
+            let left = column as f32;
+
+            // En los extremos solo una parte de la columna pertenece a la línea
+            let column_coverage = ((left + 1.0).min(b.x) - left.max(a.x)).clamp(0.0, 1.0);
+            if column_coverage <= 0.0 {
+                continue;
             }
-            
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x0 += sx;
+
+            let x = (left + 0.5).clamp(a.x, b.x);
+            let center = a.y + gradient * (x - a.x);
+            let depth = a.z + depth_gradient * (x - a.x);
+            let top = center - half_thickness;
+            let bottom = center + half_thickness;
+
+            for row in top.floor() as i32..=bottom.floor() as i32 {
+                let row_coverage = ((row as f32 + 1.0).min(bottom) - (row as f32).max(top)).clamp(0.0, 1.0);
+                let coverage = row_coverage * column_coverage;
+                if coverage > 0.0 {
+                    let (x, y) = if steep { (row, column) } else { (column, row) };
+                    self.blend_point(x, y, color, alpha * coverage, depth, BlendMode::Alpha);
+                }
             }
-            if e2 < dx {
-                err += dx;
-                y0 += sy;
+        }
+    }
+
+    // Marca un píxel como parte de la silueta del objeto seleccionado, esté tapado o no
+    pub fn mark_selected(&mut self, x: i32, y: i32) {
+        if self.inside(x, y) {
+            self.selection_mask[(y * self.width + x) as usize] = true;
+            self.selection_bounds = Some(match self.selection_bounds {
+                Some((min_x, min_y, max_x, max_y)) => (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+                None => (x, y, x, y),
+            });
+        }
+    }
+
+    // Contorno alrededor de la silueta marcada: píxeles fuera de la máscara a menos de `thickness` de ella.
+    // Se dibuja encima de todo, así el objeto seleccionado se distingue aunque esté detrás de otro.
+    pub fn draw_selection_outline(&mut self, color: Vector3, alpha: f32, thickness: i32) {
+        let Some((min_x, min_y, max_x, max_y)) = self.selection_bounds else {
+            return;
+        };
+        let masked = |framebuffer: &Framebuffer, x: i32, y: i32| {
+            x >= 0 && x < framebuffer.width && y >= 0 && y < framebuffer.height
+                && framebuffer.selection_mask[(y * framebuffer.width + x) as usize]
+        };
+
+        for y in (min_y - thickness).max(0)..=(max_y + thickness).min(self.height - 1) {
+            for x in (min_x - thickness).max(0)..=(max_x + thickness).min(self.width - 1) {
+                if masked(self, x, y) {
+                    continue;
+                }
+                let near_silhouette = (-thickness..=thickness).any(|dy| {
+                    (-thickness..=thickness).any(|dx| dx * dx + dy * dy <= thickness * thickness && masked(self, x + dx, y + dy))
+                });
+                if near_silhouette {
+                    self.blend_point(x, y, color, alpha, f32::NEG_INFINITY, BlendMode::Alpha);
+                }
             }
         }
     }
-    
+
+    // Borra color y profundidad solo dentro de un rectángulo, para dibujar otra vista encima
+    // sin que la profundidad de la escena principal la tape
+    pub fn clear_rect(&mut self, x: i32, y: i32, width: i32, height: i32) {
+        let (left, right) = (x.max(0), (x + width).min(self.width));
+        let (top, bottom) = (y.max(0), (y + height).min(self.height));
+        if left >= right || top >= bottom {
+            return;
+        }
+
+        for row in top..bottom {
+            let start = (row * self.width) as usize;
+            self.depth_buffer[start + left as usize..start + right as usize].fill(f32::INFINITY);
+        }
+        self.color_buffer.draw_rectangle(left, top, right - left, bottom - top, self.background_color);
+    }
+
+    // Oscurece (o tiñe) un rectángulo de la pantalla, para poner texto legible encima de la escena
+    pub fn shade_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Vector3, alpha: f32) {
+        for row in y.max(0)..(y + height).min(self.height) {
+            for column in x.max(0)..(x + width).min(self.width) {
+                self.blend_point(column, row, color, alpha, f32::NEG_INFINITY, BlendMode::Alpha);
+            }
+        }
+    }
+
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
     }
@@ -0,0 +1,31 @@
+// glow.rs
+use raylib::prelude::*;
+
+/// Halo drawn around a bright body, as a screen-facing disc that fades towards its edge
+#[derive(Clone)]
+pub struct Glow {
+    pub radius: f32,      // Outer radius in the body's model space
+    pub color: Vector3,
+    pub intensity: f32,   // Opacity at the center of the halo
+    pub falloff: f32,     // Higher values keep the glow closer to the body
+}
+
+impl Glow {
+    /// Warm corona around the sun
+    pub fn sun() -> Self {
+        Glow {
+            radius: 1.2,
+            color: Vector3::new(1.0, 0.75, 0.35),
+            intensity: 0.6,
+            falloff: 2.5,
+        }
+    }
+
+    /// Opacity of the halo at `distance` from the center, as a fraction of its radius
+    pub fn alpha(&self, distance: f32) -> f32 {
+        if distance >= 1.0 {
+            return 0.0;
+        }
+        (1.0 - distance).powf(self.falloff) * self.intensity
+    }
+}
@@ -0,0 +1,54 @@
+// hud.rs
+use raylib::prelude::*;
+use crate::camera::Camera;
+use crate::culling::CullStats;
+use crate::font::{TextStyle, draw_text, text_height, text_width};
+use crate::framebuffer::Framebuffer;
+
+// Distance from the corner of the screen and padding inside the panel, in pixels
+const MARGIN: i32 = 10;
+const PADDING: i32 = 6;
+
+/// Values shown by the HUD, gathered by the frame loop
+pub struct HudInfo<'a> {
+    pub fps: u32,
+    pub frame_time: f32, // Real seconds the last frame took
+    pub sim_time: f32,   // Simulation seconds since the start
+    pub time_scale: f32, // Simulation seconds per real second, zero when paused
+    pub camera: &'a Camera,
+    pub following: &'a str,
+    pub selected: Option<&'a str>,
+    pub draws: CullStats, // Meshes drawn and culled this frame
+}
+
+/// Panel in the top-left corner with performance, simulation and camera information
+pub fn draw_hud(framebuffer: &mut Framebuffer, info: &HudInfo) {
+    let camera = info.camera;
+    let speed = if info.time_scale == 0.0 { "paused".to_string() } else { format!("x{}", info.time_scale) };
+    let text = format!(
+        "FPS {}  ({:.1} ms)\nMeshes {} drawn, {} culled\nSim time {:.1} s  {}\nCamera ({:.1}, {:.1}, {:.1})\nMode {:?}, {:?}\nFollowing {}\nSelected {}",
+        info.fps,
+        info.frame_time * 1000.0,
+        info.draws.drawn,
+        info.draws.culled,
+        info.sim_time,
+        speed,
+        camera.eye.x,
+        camera.eye.y,
+        camera.eye.z,
+        camera.mode,
+        camera.tracking,
+        info.following,
+        info.selected.unwrap_or("-"),
+    );
+
+    framebuffer.shade_rect(
+        MARGIN,
+        MARGIN,
+        text_width(&text, 1) + 2 * PADDING,
+        text_height(&text, 1) + 2 * PADDING,
+        Vector3::new(0.0, 0.0, 0.05),
+        0.6,
+    );
+    draw_text(framebuffer, &text, MARGIN + PADDING, MARGIN + PADDING, &TextStyle::new(Vector3::new(0.85, 0.95, 1.0), 1));
+}
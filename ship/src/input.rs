@@ -0,0 +1,526 @@
+// input.rs
+use raylib::prelude::*;
+use raylib::consts::KeyboardKey::*;
+use crate::camera::CameraMode;
+use crate::font::{TextStyle, draw_text};
+use crate::framebuffer::Framebuffer;
+use std::fs;
+use std::io;
+
+// Only the first gamepad is read
+const GAMEPAD: i32 = 0;
+
+/// Part of the program an action belongs to. Orbit, free-fly and ship controls are never
+/// active at the same time, so they may share inputs; global actions must not share with anyone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Context {
+    Global,
+    Orbit,
+    FreeFly,
+    Ship, // Chase and cockpit cameras
+}
+
+impl Context {
+    pub const ALL: [Context; 4] = [Context::Global, Context::Orbit, Context::FreeFly, Context::Ship];
+
+    /// Context whose controls are read with the camera in `mode`
+    pub fn for_camera(mode: CameraMode) -> Context {
+        match mode {
+            CameraMode::Orbit => Context::Orbit,
+            CameraMode::FreeFly => Context::FreeFly,
+            CameraMode::Chase | CameraMode::Cockpit => Context::Ship,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Context::Global => "Global",
+            Context::Orbit => "Orbit camera",
+            Context::FreeFly => "Free-fly camera",
+            Context::Ship => "Ship (chase / cockpit)",
+        }
+    }
+
+    /// Whether actions of both contexts can be read in the same frame
+    fn overlaps(self, other: Context) -> bool {
+        self == other || self == Context::Global || other == Context::Global
+    }
+}
+
+/// Side of a gamepad axis an input reads
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Positive,
+    Negative,
+}
+
+/// A button, or one direction of a stick or trigger, that can trigger an action
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    Analog(GamepadAxis, Direction), // Gives a value between 0 and 1, counts as held past half travel
+}
+
+impl Input {
+    /// Name used in the controls file
+    pub fn name(self) -> &'static str {
+        INPUT_NAMES
+            .iter()
+            .find(|(_, input)| *input == self)
+            .map_or("?", |(name, _)| name)
+    }
+
+    fn parse(name: &str) -> Option<Input> {
+        INPUT_NAMES
+            .iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+            .map(|(_, input)| *input)
+    }
+
+    fn is_down(self, window: &RaylibHandle) -> bool {
+        match self {
+            Input::Key(key) => window.is_key_down(key),
+            Input::Mouse(button) => window.is_mouse_button_down(button),
+            Input::Gamepad(button) => window.is_gamepad_available(GAMEPAD) && window.is_gamepad_button_down(GAMEPAD, button),
+            Input::Analog(axis, direction) => raw_axis(window, axis, direction) > 0.5,
+        }
+    }
+
+    fn is_pressed(self, window: &RaylibHandle) -> bool {
+        match self {
+            Input::Key(key) => window.is_key_pressed(key),
+            Input::Mouse(button) => window.is_mouse_button_pressed(button),
+            Input::Gamepad(button) => window.is_gamepad_available(GAMEPAD) && window.is_gamepad_button_pressed(GAMEPAD, button),
+            Input::Analog(..) => false, // Sticks and triggers only drive held actions
+        }
+    }
+}
+
+/// How stick and trigger positions become action values
+#[derive(Clone, Copy, Debug)]
+pub struct AnalogSettings {
+    pub stick_deadzone: f32,   // Stick deflection ignored around the center, measured on both axes together
+    pub trigger_deadzone: f32, // Trigger travel ignored at rest
+    pub response_curve: f32,   // Exponent applied past the deadzone: 1 is linear, higher gives finer control near the center
+}
+
+impl Default for AnalogSettings {
+    fn default() -> Self {
+        AnalogSettings { stick_deadzone: 0.15, trigger_deadzone: 0.05, response_curve: 2.0 }
+    }
+}
+
+impl AnalogSettings {
+    /// Setting with the given name in the controls file
+    fn setting_mut(&mut self, name: &str) -> Option<&mut f32> {
+        match name {
+            "stick_deadzone" => Some(&mut self.stick_deadzone),
+            "trigger_deadzone" => Some(&mut self.trigger_deadzone),
+            "response_curve" => Some(&mut self.response_curve),
+            _ => None,
+        }
+    }
+
+    /// Value of one direction of a gamepad axis, from 0 to 1 after the deadzone and curve
+    fn read(&self, window: &RaylibHandle, axis: GamepadAxis, direction: Direction) -> f32 {
+        if !window.is_gamepad_available(GAMEPAD) {
+            return 0.0;
+        }
+
+        let value = match stick_axes(axis) {
+            // The deadzone is radial, so diagonals aren't cut off near the center
+            Some((x_axis, y_axis)) => {
+                let stick = Vector2::new(
+                    window.get_gamepad_axis_movement(GAMEPAD, x_axis),
+                    window.get_gamepad_axis_movement(GAMEPAD, y_axis),
+                );
+                let magnitude = stick.length();
+                if magnitude <= 0.0 {
+                    return 0.0;
+                }
+                let component = if axis == x_axis { stick.x } else { stick.y };
+                component / magnitude * self.shape(magnitude, self.stick_deadzone)
+            }
+            None => self.shape(trigger_pull(window, axis), self.trigger_deadzone),
+        };
+
+        match direction {
+            Direction::Positive => value.max(0.0),
+            Direction::Negative => (-value).max(0.0),
+        }
+    }
+
+    /// Rescales `amount` so the end of the deadzone reads 0, then applies the response curve
+    fn shape(&self, amount: f32, deadzone: f32) -> f32 {
+        if amount <= deadzone {
+            return 0.0;
+        }
+        ((amount - deadzone) / (1.0 - deadzone).max(1e-3)).min(1.0).powf(self.response_curve.max(0.1))
+    }
+}
+
+/// Both axes of the stick `axis` belongs to, `None` for the triggers
+fn stick_axes(axis: GamepadAxis) -> Option<(GamepadAxis, GamepadAxis)> {
+    match axis {
+        GamepadAxis::GAMEPAD_AXIS_LEFT_X | GamepadAxis::GAMEPAD_AXIS_LEFT_Y => {
+            Some((GamepadAxis::GAMEPAD_AXIS_LEFT_X, GamepadAxis::GAMEPAD_AXIS_LEFT_Y))
+        }
+        GamepadAxis::GAMEPAD_AXIS_RIGHT_X | GamepadAxis::GAMEPAD_AXIS_RIGHT_Y => {
+            Some((GamepadAxis::GAMEPAD_AXIS_RIGHT_X, GamepadAxis::GAMEPAD_AXIS_RIGHT_Y))
+        }
+        GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER | GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER => None,
+    }
+}
+
+/// How far a trigger is pulled, from 0 to 1. Raylib reports -1 at rest and 1 fully pulled.
+fn trigger_pull(window: &RaylibHandle, axis: GamepadAxis) -> f32 {
+    (window.get_gamepad_axis_movement(GAMEPAD, axis) + 1.0) * 0.5
+}
+
+/// Unshaped value of one direction of an axis, used to treat sticks and triggers as buttons
+fn raw_axis(window: &RaylibHandle, axis: GamepadAxis, direction: Direction) -> f32 {
+    if !window.is_gamepad_available(GAMEPAD) {
+        return 0.0;
+    }
+    let value = match stick_axes(axis) {
+        Some(_) => window.get_gamepad_axis_movement(GAMEPAD, axis),
+        None => trigger_pull(window, axis),
+    };
+    match direction {
+        Direction::Positive => value,
+        Direction::Negative => -value,
+    }
+}
+
+// Gamepad inputs, named after an Xbox layout
+const PAD_A: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+const PAD_B: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT);
+const PAD_X: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT);
+const PAD_Y: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP);
+const PAD_UP: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP);
+const PAD_DOWN: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN);
+const PAD_LEFT: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT);
+const PAD_RIGHT: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT);
+const PAD_LB: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1);
+const PAD_RB: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1);
+const PAD_BACK: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT);
+const PAD_START: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT);
+const PAD_L3: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB);
+const PAD_R3: Input = Input::Gamepad(GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB);
+const PAD_LT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER, Direction::Positive);
+const PAD_RT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER, Direction::Positive);
+const PAD_LSTICK_LEFT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_LEFT_X, Direction::Negative);
+const PAD_LSTICK_RIGHT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_LEFT_X, Direction::Positive);
+const PAD_LSTICK_UP: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_LEFT_Y, Direction::Negative);
+const PAD_LSTICK_DOWN: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_LEFT_Y, Direction::Positive);
+const PAD_RSTICK_LEFT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_RIGHT_X, Direction::Negative);
+const PAD_RSTICK_RIGHT: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_RIGHT_X, Direction::Positive);
+const PAD_RSTICK_UP: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_RIGHT_Y, Direction::Negative);
+const PAD_RSTICK_DOWN: Input = Input::Analog(GamepadAxis::GAMEPAD_AXIS_RIGHT_Y, Direction::Positive);
+
+macro_rules! actions {
+    ($($variant:ident => $name:literal, $context:ident, [$($input:expr),*];)*) => {
+        /// Something the user can do, bound to any number of inputs
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub enum Action {
+            $($variant,)*
+        }
+
+        impl Action {
+            pub const ALL: &'static [Action] = &[$(Action::$variant,)*];
+
+            /// Name used in the controls file
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Action::$variant => $name,)*
+                }
+            }
+
+            pub fn context(self) -> Context {
+                match self {
+                    $(Action::$variant => Context::$context,)*
+                }
+            }
+
+            fn default_inputs(self) -> Vec<Input> {
+                match self {
+                    $(Action::$variant => vec![$($input),*],)*
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    WarpHome => "warp_home", Global, [Input::Key(KEY_ONE), PAD_START];
+    WarpTop => "warp_top", Global, [Input::Key(KEY_TWO), PAD_UP];
+    WarpEarth => "warp_earth", Global, [Input::Key(KEY_THREE), PAD_LEFT];
+    WarpMars => "warp_mars", Global, [Input::Key(KEY_FOUR), PAD_DOWN];
+    WarpUranus => "warp_uranus", Global, [Input::Key(KEY_FIVE), PAD_RIGHT];
+    CycleCamera => "cycle_camera", Global, [Input::Key(KEY_C), PAD_Y];
+    CycleTracking => "cycle_tracking", Global, [Input::Key(KEY_T), PAD_X];
+    CycleFocus => "cycle_focus", Global, [Input::Key(KEY_TAB), PAD_B];
+    ToggleWireframe => "toggle_wireframe", Global, [Input::Key(KEY_Z)];
+    LodColors => "lod_colors", Global, [Input::Key(KEY_X)];
+    FlightAssist => "flight_assist", Global, [Input::Key(KEY_V), PAD_A];
+    CollisionResponse => "collision_response", Global, [Input::Key(KEY_B)];
+    TogglePrediction => "toggle_prediction", Global, [Input::Key(KEY_P), PAD_R3];
+    AddKeyframe => "add_keyframe", Global, [Input::Key(KEY_K)];
+    SavePath => "save_path", Global, [Input::Key(KEY_F5)];
+    LoadPath => "load_path", Global, [Input::Key(KEY_F6)];
+    PlayPath => "play_path", Global, [Input::Key(KEY_F7)];
+    ToggleHelp => "toggle_help", Global, [Input::Key(KEY_F1), PAD_BACK];
+    Select => "select", Global, [Input::Mouse(MouseButton::MOUSE_BUTTON_RIGHT)];
+    WarpSelected => "warp_selected", Global, [Input::Key(KEY_G), PAD_L3];
+    ToggleHud => "toggle_hud", Global, [Input::Key(KEY_H)];
+    ToggleLabels => "toggle_labels", Global, [Input::Key(KEY_L)];
+    ToggleMinimap => "toggle_minimap", Global, [Input::Key(KEY_M)];
+    SplitView => "split_view", Global, [Input::Key(KEY_O)];
+    TimeSlower => "time_slower", Global, [Input::Key(KEY_LEFT_BRACKET)];
+    TimeFaster => "time_faster", Global, [Input::Key(KEY_RIGHT_BRACKET)];
+    Pause => "pause", Global, [Input::Key(KEY_SPACE)];
+
+    YawLeft => "yaw_left", Orbit, [Input::Key(KEY_A), PAD_RSTICK_LEFT];
+    YawRight => "yaw_right", Orbit, [Input::Key(KEY_D), PAD_RSTICK_RIGHT];
+    PitchUp => "pitch_up", Orbit, [Input::Key(KEY_W), PAD_RSTICK_UP];
+    PitchDown => "pitch_down", Orbit, [Input::Key(KEY_S), PAD_RSTICK_DOWN];
+    ZoomIn => "zoom_in", Orbit, [Input::Key(KEY_UP), PAD_RT];
+    ZoomOut => "zoom_out", Orbit, [Input::Key(KEY_DOWN), PAD_LT];
+    PanLeft => "pan_left", Orbit, [Input::Key(KEY_Q), Input::Key(KEY_LEFT), PAD_LSTICK_LEFT];
+    PanRight => "pan_right", Orbit, [Input::Key(KEY_E), Input::Key(KEY_RIGHT), PAD_LSTICK_RIGHT];
+    PanUp => "pan_up", Orbit, [Input::Key(KEY_R), PAD_LSTICK_UP];
+    PanDown => "pan_down", Orbit, [Input::Key(KEY_F), PAD_LSTICK_DOWN];
+    OrbitDrag => "orbit_drag", Orbit, [Input::Mouse(MouseButton::MOUSE_BUTTON_LEFT)];
+    PanDrag => "pan_drag", Orbit, [Input::Mouse(MouseButton::MOUSE_BUTTON_MIDDLE)];
+    PanModifier => "pan_modifier", Orbit, [Input::Key(KEY_LEFT_SHIFT), Input::Key(KEY_RIGHT_SHIFT)];
+
+    FlyForward => "fly_forward", FreeFly, [Input::Key(KEY_W), PAD_RT];
+    FlyBackward => "fly_backward", FreeFly, [Input::Key(KEY_S), PAD_LT];
+    FlyLeft => "fly_left", FreeFly, [Input::Key(KEY_A), PAD_LSTICK_LEFT];
+    FlyRight => "fly_right", FreeFly, [Input::Key(KEY_D), PAD_LSTICK_RIGHT];
+    FlyUp => "fly_up", FreeFly, [Input::Key(KEY_R), PAD_LSTICK_UP];
+    FlyDown => "fly_down", FreeFly, [Input::Key(KEY_F), PAD_LSTICK_DOWN];
+    LookLeft => "look_left", FreeFly, [Input::Key(KEY_LEFT), PAD_RSTICK_LEFT];
+    LookRight => "look_right", FreeFly, [Input::Key(KEY_RIGHT), PAD_RSTICK_RIGHT];
+    LookUp => "look_up", FreeFly, [Input::Key(KEY_UP), PAD_RSTICK_UP];
+    LookDown => "look_down", FreeFly, [Input::Key(KEY_DOWN), PAD_RSTICK_DOWN];
+    RollLeft => "roll_left", FreeFly, [Input::Key(KEY_Q), PAD_LB];
+    RollRight => "roll_right", FreeFly, [Input::Key(KEY_E), PAD_RB];
+    LookDrag => "look_drag", FreeFly, [Input::Mouse(MouseButton::MOUSE_BUTTON_LEFT)];
+
+    // Flight-stick convention: pulling the right stick back pitches the nose up
+    ShipForward => "ship_forward", Ship, [Input::Key(KEY_W), PAD_RT];
+    ShipBackward => "ship_backward", Ship, [Input::Key(KEY_S), PAD_LT];
+    ShipLeft => "ship_left", Ship, [Input::Key(KEY_LEFT), PAD_LSTICK_LEFT];
+    ShipRight => "ship_right", Ship, [Input::Key(KEY_RIGHT), PAD_LSTICK_RIGHT];
+    ShipUp => "ship_up", Ship, [Input::Key(KEY_R), PAD_LSTICK_UP];
+    ShipDown => "ship_down", Ship, [Input::Key(KEY_F), PAD_LSTICK_DOWN];
+    ShipYawLeft => "ship_yaw_left", Ship, [Input::Key(KEY_A), PAD_RSTICK_LEFT];
+    ShipYawRight => "ship_yaw_right", Ship, [Input::Key(KEY_D), PAD_RSTICK_RIGHT];
+    ShipPitchUp => "ship_pitch_up", Ship, [Input::Key(KEY_UP), PAD_RSTICK_DOWN];
+    ShipPitchDown => "ship_pitch_down", Ship, [Input::Key(KEY_DOWN), PAD_RSTICK_UP];
+    ShipRollLeft => "ship_roll_left", Ship, [Input::Key(KEY_Q), PAD_LB];
+    ShipRollRight => "ship_roll_right", Ship, [Input::Key(KEY_E), PAD_RB];
+}
+
+/// The same input bound to two actions that can be read in the same frame
+pub struct Conflict {
+    pub input: Input,
+    pub first: Action,
+    pub second: Action,
+}
+
+/// Inputs bound to every action
+pub struct Bindings {
+    inputs: Vec<Vec<Input>>, // Indexed by `Action as usize`
+    pub analog: AnalogSettings,
+}
+
+impl Bindings {
+    pub fn defaults() -> Self {
+        Bindings {
+            inputs: Action::ALL.iter().map(|action| action.default_inputs()).collect(),
+            analog: AnalogSettings::default(),
+        }
+    }
+
+    /// Defaults overridden by the lines of a controls file, `action = INPUT, INPUT, ...`
+    /// or `setting = NUMBER` for the analog settings. An empty list unbinds the action.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn load(path: &str) -> io::Result<Self> {
+        let mut bindings = Bindings::defaults();
+
+        for (line_number, line) in fs::read_to_string(path)?.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("{path}:{}: {message}", line_number + 1));
+            let (name, inputs) = line.split_once('=').ok_or_else(|| invalid("expected `action = INPUT, ...`".to_string()))?;
+            let name = name.trim();
+            if let Some(setting) = bindings.analog.setting_mut(name) {
+                let value = inputs.trim();
+                *setting = value.parse().map_err(|error| invalid(format!("{value:?}: {error}")))?;
+                continue;
+            }
+
+            let action = Action::ALL
+                .iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| invalid(format!("unknown action {name:?}")))?;
+            let inputs = inputs
+                .split(',')
+                .map(str::trim)
+                .filter(|input| !input.is_empty())
+                .map(|input| Input::parse(input).ok_or_else(|| invalid(format!("unknown input {input:?}"))))
+                .collect::<io::Result<Vec<Input>>>()?;
+
+            bindings.inputs[*action as usize] = inputs;
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn inputs(&self, action: Action) -> &[Input] {
+        &self.inputs[action as usize]
+    }
+
+    /// Whether any input of `action` is held
+    pub fn is_down(&self, window: &RaylibHandle, action: Action) -> bool {
+        self.inputs(action).iter().any(|input| input.is_down(window))
+    }
+
+    /// Strongest input of `action`, from 0 to 1: buttons give 0 or 1, sticks and triggers anything in between
+    pub fn value(&self, window: &RaylibHandle, action: Action) -> f32 {
+        self.inputs(action)
+            .iter()
+            .map(|input| match *input {
+                Input::Analog(axis, direction) => self.analog.read(window, axis, direction),
+                _ if input.is_down(window) => 1.0,
+                _ => 0.0,
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// Whether any input of `action` went down this frame
+    pub fn is_pressed(&self, window: &RaylibHandle, action: Action) -> bool {
+        self.inputs(action).iter().any(|input| input.is_pressed(window))
+    }
+
+    /// Value between -1 and 1: how much `positive` is held minus how much `negative` is
+    pub fn axis(&self, window: &RaylibHandle, positive: Action, negative: Action) -> f32 {
+        (self.value(window, positive) - self.value(window, negative)).clamp(-1.0, 1.0)
+    }
+
+    /// Inputs bound to more than one action of overlapping contexts
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for (i, &first) in Action::ALL.iter().enumerate() {
+            for &second in &Action::ALL[i + 1..] {
+                if !first.context().overlaps(second.context()) {
+                    continue;
+                }
+                for &input in self.inputs(first) {
+                    if self.inputs(second).contains(&input) {
+                        conflicts.push(Conflict { input, first, second });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Inputs of `action` as written in the controls file
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<&str> = self.inputs(action).iter().map(|input| input.name()).collect();
+        if names.is_empty() { "-".to_string() } else { names.join(", ") }
+    }
+
+    /// Lists every binding over the frame, one column per context.
+    /// The context in use is highlighted and conflicting actions are drawn in red.
+    pub fn draw_help(&self, framebuffer: &mut Framebuffer, active: Context) {
+        const LINE_HEIGHT: i32 = 12;
+        const MARGIN: i32 = 20;
+        const KEY_OFFSET: i32 = 125;
+
+        framebuffer.shade_rect(0, 0, framebuffer.width, framebuffer.height, Vector3::new(0.0, 0.0, 0.05), 0.75);
+
+        let conflicts = self.conflicts();
+        let in_conflict = |action: Action| conflicts.iter().any(|conflict| conflict.first == action || conflict.second == action);
+        let column_width = (framebuffer.width - 2 * MARGIN) / Context::ALL.len() as i32;
+
+        let white = TextStyle::new(Vector3::new(1.0, 1.0, 1.0), 1);
+        let gray = TextStyle::new(Vector3::new(0.6, 0.6, 0.6), 1);
+        let gold = TextStyle::new(Vector3::new(1.0, 0.8, 0.2), 1);
+        let red = TextStyle::new(Vector3::new(1.0, 0.3, 0.3), 1);
+
+        draw_text(framebuffer, "Controls (F1 to close) - edit controls.txt to rebind", MARGIN, MARGIN, &TextStyle { scale: 2, ..white });
+        let analog = format!(
+            "Gamepad: stick deadzone {:.2}, trigger deadzone {:.2}, response curve {:.1}",
+            self.analog.stick_deadzone, self.analog.trigger_deadzone, self.analog.response_curve,
+        );
+        draw_text(framebuffer, &analog, MARGIN, MARGIN + 24, &gray);
+
+        for (column, context) in Context::ALL.iter().enumerate() {
+            let x = MARGIN + column as i32 * column_width;
+            let mut y = MARGIN + 48;
+
+            let title = if *context == active || *context == Context::Global { &gold } else { &gray };
+            draw_text(framebuffer, context.title(), x, y, title);
+            y += LINE_HEIGHT + 6;
+
+            for action in Action::ALL.iter().filter(|action| action.context() == *context) {
+                let style = if in_conflict(*action) { &red } else { &white };
+                draw_text(framebuffer, action.name(), x, y, style);
+                draw_text(framebuffer, &self.describe(*action), x + KEY_OFFSET, y, style);
+                y += LINE_HEIGHT;
+            }
+        }
+
+        // Conflicts are listed at the bottom so they can be fixed in the file
+        let mut y = framebuffer.height - MARGIN - conflicts.len() as i32 * LINE_HEIGHT;
+        for conflict in &conflicts {
+            let message = format!("Conflict: {} is bound to {} and {}", conflict.input.name(), conflict.first.name(), conflict.second.name());
+            draw_text(framebuffer, &message, MARGIN, y, &red);
+            y += LINE_HEIGHT;
+        }
+    }
+}
+
+// Names accepted in the controls file
+const INPUT_NAMES: &[(&str, Input)] = &[
+    ("A", Input::Key(KEY_A)), ("B", Input::Key(KEY_B)), ("C", Input::Key(KEY_C)), ("D", Input::Key(KEY_D)),
+    ("E", Input::Key(KEY_E)), ("F", Input::Key(KEY_F)), ("G", Input::Key(KEY_G)), ("H", Input::Key(KEY_H)),
+    ("I", Input::Key(KEY_I)), ("J", Input::Key(KEY_J)), ("K", Input::Key(KEY_K)), ("L", Input::Key(KEY_L)),
+    ("M", Input::Key(KEY_M)), ("N", Input::Key(KEY_N)), ("O", Input::Key(KEY_O)), ("P", Input::Key(KEY_P)),
+    ("Q", Input::Key(KEY_Q)), ("R", Input::Key(KEY_R)), ("S", Input::Key(KEY_S)), ("T", Input::Key(KEY_T)),
+    ("U", Input::Key(KEY_U)), ("V", Input::Key(KEY_V)), ("W", Input::Key(KEY_W)), ("X", Input::Key(KEY_X)),
+    ("Y", Input::Key(KEY_Y)), ("Z", Input::Key(KEY_Z)),
+    ("0", Input::Key(KEY_ZERO)), ("1", Input::Key(KEY_ONE)), ("2", Input::Key(KEY_TWO)), ("3", Input::Key(KEY_THREE)),
+    ("4", Input::Key(KEY_FOUR)), ("5", Input::Key(KEY_FIVE)), ("6", Input::Key(KEY_SIX)), ("7", Input::Key(KEY_SEVEN)),
+    ("8", Input::Key(KEY_EIGHT)), ("9", Input::Key(KEY_NINE)),
+    ("F1", Input::Key(KEY_F1)), ("F2", Input::Key(KEY_F2)), ("F3", Input::Key(KEY_F3)), ("F4", Input::Key(KEY_F4)),
+    ("F5", Input::Key(KEY_F5)), ("F6", Input::Key(KEY_F6)), ("F7", Input::Key(KEY_F7)), ("F8", Input::Key(KEY_F8)),
+    ("F9", Input::Key(KEY_F9)), ("F10", Input::Key(KEY_F10)), ("F11", Input::Key(KEY_F11)), ("F12", Input::Key(KEY_F12)),
+    ("UP", Input::Key(KEY_UP)), ("DOWN", Input::Key(KEY_DOWN)), ("LEFT", Input::Key(KEY_LEFT)), ("RIGHT", Input::Key(KEY_RIGHT)),
+    ("MINUS", Input::Key(KEY_MINUS)), ("EQUAL", Input::Key(KEY_EQUAL)), ("COMMA", Input::Key(KEY_COMMA)), ("PERIOD", Input::Key(KEY_PERIOD)),
+    ("LEFT_BRACKET", Input::Key(KEY_LEFT_BRACKET)), ("RIGHT_BRACKET", Input::Key(KEY_RIGHT_BRACKET)),
+    ("SPACE", Input::Key(KEY_SPACE)), ("ENTER", Input::Key(KEY_ENTER)), ("TAB", Input::Key(KEY_TAB)),
+    ("BACKSPACE", Input::Key(KEY_BACKSPACE)), ("INSERT", Input::Key(KEY_INSERT)), ("DELETE", Input::Key(KEY_DELETE)),
+    ("HOME", Input::Key(KEY_HOME)), ("END", Input::Key(KEY_END)), ("PAGE_UP", Input::Key(KEY_PAGE_UP)), ("PAGE_DOWN", Input::Key(KEY_PAGE_DOWN)),
+    ("LEFT_SHIFT", Input::Key(KEY_LEFT_SHIFT)), ("RIGHT_SHIFT", Input::Key(KEY_RIGHT_SHIFT)),
+    ("LEFT_CONTROL", Input::Key(KEY_LEFT_CONTROL)), ("RIGHT_CONTROL", Input::Key(KEY_RIGHT_CONTROL)),
+    ("LEFT_ALT", Input::Key(KEY_LEFT_ALT)), ("RIGHT_ALT", Input::Key(KEY_RIGHT_ALT)),
+    ("MOUSE_LEFT", Input::Mouse(MouseButton::MOUSE_BUTTON_LEFT)),
+    ("MOUSE_RIGHT", Input::Mouse(MouseButton::MOUSE_BUTTON_RIGHT)),
+    ("MOUSE_MIDDLE", Input::Mouse(MouseButton::MOUSE_BUTTON_MIDDLE)),
+    ("PAD_A", PAD_A), ("PAD_B", PAD_B), ("PAD_X", PAD_X), ("PAD_Y", PAD_Y),
+    ("PAD_UP", PAD_UP), ("PAD_DOWN", PAD_DOWN), ("PAD_LEFT", PAD_LEFT), ("PAD_RIGHT", PAD_RIGHT),
+    ("PAD_LB", PAD_LB), ("PAD_RB", PAD_RB), ("PAD_BACK", PAD_BACK), ("PAD_START", PAD_START),
+    ("PAD_L3", PAD_L3), ("PAD_R3", PAD_R3), ("PAD_LT", PAD_LT), ("PAD_RT", PAD_RT),
+    ("PAD_LSTICK_LEFT", PAD_LSTICK_LEFT), ("PAD_LSTICK_RIGHT", PAD_LSTICK_RIGHT), ("PAD_LSTICK_UP", PAD_LSTICK_UP), ("PAD_LSTICK_DOWN", PAD_LSTICK_DOWN),
+    ("PAD_RSTICK_LEFT", PAD_RSTICK_LEFT), ("PAD_RSTICK_RIGHT", PAD_RSTICK_RIGHT), ("PAD_RSTICK_UP", PAD_RSTICK_UP), ("PAD_RSTICK_DOWN", PAD_RSTICK_DOWN),
+];
@@ -0,0 +1,106 @@
+// labels.rs
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::font::{TextStyle, draw_text, text_height, text_width};
+use crate::framebuffer::Framebuffer;
+use crate::line3d::project_point;
+use crate::picking::{Ray, ray_sphere};
+use crate::viewport::Viewport;
+
+// Pixels between the top of the object and its label, and between stacked labels
+const GAP: i32 = 6;
+// How many times a label moves up to get out of the way before it is hidden
+const MAX_SHIFTS: i32 = 3;
+
+/// Object that gets a floating label, approximated by a sphere
+pub struct LabelTarget<'a> {
+    pub name: &'a str,
+    pub position: Vector3, // World-space center
+    pub radius: f32,
+}
+
+/// Draws the name and distance of each target above its projected center.
+/// Labels are skipped for objects behind the camera, outside `viewport` or hidden behind another target;
+/// nearer objects get their place first and farther labels move up or hide when they would overlap.
+/// `uniforms` must use the identity as model matrix.
+pub fn draw_labels(framebuffer: &mut Framebuffer, targets: &[LabelTarget], uniforms: &Uniforms, viewport: &Viewport) {
+    let eye = uniforms.camera_position;
+    // Second row of the view matrix: the camera's up vector in world space
+    let view = &uniforms.view_matrix;
+    let camera_up = Vector3::new(view.m1, view.m5, view.m9);
+
+    let mut order: Vec<usize> = (0..targets.len()).collect();
+    order.sort_by(|&a, &b| eye.distance_to(targets[a].position).total_cmp(&eye.distance_to(targets[b].position)));
+
+    let name_style = TextStyle::new(Vector3::new(1.0, 1.0, 1.0), 1);
+    let distance_style = TextStyle::new(Vector3::new(0.7, 0.8, 0.9), 1);
+    let leader_color = Vector3::new(0.7, 0.8, 0.9);
+
+    let mut placed: Vec<(i32, i32, i32, i32)> = Vec::new();
+    for index in order {
+        let target = &targets[index];
+        if is_occluded(target, targets, eye) {
+            continue;
+        }
+
+        // The label hangs from the top of the object as seen from the camera
+        let Some(center) = project_point(target.position, uniforms) else {
+            continue;
+        };
+        let Some(top) = project_point(target.position + camera_up * target.radius, uniforms) else {
+            continue;
+        };
+        if !viewport.contains(Vector2::new(center.x, center.y)) {
+            continue;
+        }
+
+        let distance = format!("{:.1}", eye.distance_to(target.position));
+        let width = text_width(target.name, 1).max(text_width(&distance, 1));
+        let height = text_height(target.name, 1) + text_height(&distance, 1) + 2;
+
+        let x = center.x as i32 - width / 2;
+        let mut y = top.y.min(center.y) as i32 - GAP - height;
+        let mut shifts = 0;
+        while shifts <= MAX_SHIFTS && placed.iter().any(|rect| overlaps(*rect, (x, y, width, height))) {
+            y -= height + GAP;
+            shifts += 1;
+        }
+        if shifts > MAX_SHIFTS {
+            continue;
+        }
+        placed.push((x, y, width, height));
+
+        // Leader line from the object to the bottom of its label
+        framebuffer.draw_line_aa(
+            Vector3::new(center.x, top.y.min(center.y), -1.0),
+            Vector3::new(center.x, (y + height) as f32 + 1.0, -1.0),
+            leader_color,
+            0.5,
+            1.0,
+        );
+        draw_text(framebuffer, target.name, center.x as i32 - text_width(target.name, 1) / 2, y, &name_style);
+        draw_text(framebuffer, &distance, center.x as i32 - text_width(&distance, 1) / 2, y + height - text_height(&distance, 1), &distance_style);
+    }
+}
+
+/// Whether another target stands between the camera and the visible side of `target`
+fn is_occluded(target: &LabelTarget, targets: &[LabelTarget], eye: Vector3) -> bool {
+    let offset = target.position - eye;
+    let distance = offset.length();
+    if distance <= target.radius {
+        return false; // Inside the object, nothing can be in front of it
+    }
+
+    let ray = Ray { origin: eye, direction: offset / distance };
+    let visible_side = distance - target.radius;
+    targets
+        .iter()
+        .filter(|other| !std::ptr::eq(*other, target))
+        .filter_map(|other| ray_sphere(ray, other.position, other.radius))
+        .any(|hit| hit > 0.0 && hit < visible_side)
+}
+
+/// Whether two (x, y, width, height) rectangles intersect
+fn overlaps(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> bool {
+    a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+}
@@ -1,54 +1,81 @@
 // line.rs
+use crate::Uniforms;
 use crate::fragment::Fragment;
+use crate::line3d::{clip_range, lerp_clip, to_clip, to_screen};
 use crate::vertex::Vertex;
-use raylib::math::Vector3;
+use raylib::math::{Vector3, Vector4};
 
-pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
+pub fn line(a: &Vertex, b: &Vertex, uniforms: &Uniforms) -> Vec<Fragment> {
     let mut fragments = Vec::new();
-    
-    let start = a.transformed_position;
-    let end = b.transformed_position;
-    
+
+    // Recortar la arista en espacio de recorte, antes de dividir por w: un vértice detrás de la cámara
+    // aparecería reflejado al otro lado de la pantalla y uno con w casi cero se proyectaría al infinito
+    let clip_a = to_clip(a.position, uniforms);
+    let clip_b = to_clip(b.position, uniforms);
+    let Some((t_start, t_end)) = clip_range(clip_a, clip_b) else {
+        return fragments;
+    };
+
+    // Los extremos recortados quedan sobre los bordes del volumen de visión. Se fijan dentro para que
+    // el redondeo no los saque del viewport y el recorrido de Bresenham no pase de su rectángulo
+    let to_viewport = |t: f32| {
+        let p = lerp_clip(clip_a, clip_b, t);
+        let clamped = Vector4::new(p.x.clamp(-p.w, p.w), p.y.clamp(-p.w, p.w), p.z, p.w);
+        to_screen(clamped, &uniforms.viewport_matrix)
+    };
+    let start = to_viewport(t_start);
+    let end = to_viewport(t_end);
+
+    // Posición en el espacio del modelo de los extremos recortados
+    let start_position = a.position.lerp(b.position, t_start);
+    let end_position = a.position.lerp(b.position, t_end);
+
     let mut x0 = start.x as i32;
     let mut y0 = start.y as i32;
     let x1 = end.x as i32;
     let y1 = end.y as i32;
-    
+
     let dx = (x1 - x0).abs();
     let dy = (y1 - y0).abs();
-    
+
     let sx = if x0 < x1 { 1 } else { -1 };
     let sy = if y0 < y1 { 1 } else { -1 };
-    
-    let mut err = if dx > dy { dx / 2 } else { -dy / 2 };
-    
+
+    let mut err = dx - dy;
+
+    // Número de pasos del eje mayor, para interpolar a lo largo de la línea
+    let steps = dx.max(dy).max(1) as f32;
+    let mut step = 0;
+
     loop {
-        let z = if (end.x - start.x).abs() > 0.001 { 
-            start.z + (end.z - start.z) * (x0 - start.x as i32) as f32 / (end.x - start.x) as f32 
-        } else { 
-            start.z 
-        };
-        
-        // Usar el color interpolado o blanco por defecto
+        let t = step as f32 / steps;
+
+        // Interpolar profundidad y posición en el espacio del modelo, igual que en los triángulos
+        let depth = start.z + (end.z - start.z) * t;
+        let world_pos = start_position + (end_position - start_position) * t;
+
+        // Blanco por defecto, los shaders de cada cuerpo deciden el color final
         fragments.push(Fragment::new(
-            x0 as f32, 
-            y0 as f32, 
-            Vector3::new(1.0, 1.0, 1.0), 
-            z
+            x0 as f32 + 0.5,
+            y0 as f32 + 0.5,
+            Vector3::new(1.0, 1.0, 1.0),
+            depth,
+            world_pos,
         ));
-        
+
         if x0 == x1 && y0 == y1 { break; }
-        
-        let e2 = err;
-        if e2 > -dx {
+
+        let e2 = 2 * err;
+        if e2 > -dy {
             err -= dy;
             x0 += sx;
         }
-        if e2 < dy {
+        if e2 < dx {
             err += dx;
             y0 += sy;
         }
+        step += 1;
     }
-    
+
     fragments
-}
\ No newline at end of file
+}
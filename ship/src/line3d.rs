@@ -0,0 +1,182 @@
+// line3d.rs
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::multiply_matrix_vector4;
+
+/// Appearance of a 3D line
+#[derive(Clone)]
+pub struct LineStyle {
+    pub color: Vector3,
+    pub alpha: f32,
+    pub width: f32,                        // Width in pixels
+    pub dash: Option<(f32, f32)>,          // (dash, gap) lengths in world units
+    pub fade_distance: Option<(f32, f32)>, // Alpha fades out between these distances from the camera
+}
+
+impl LineStyle {
+    pub fn solid(color: Vector3, alpha: f32, width: f32) -> Self {
+        LineStyle {
+            color,
+            alpha,
+            width,
+            dash: None,
+            fade_distance: None,
+        }
+    }
+
+    fn alpha_at(&self, distance: f32) -> f32 {
+        match self.fade_distance {
+            Some((start, end)) if end > start => {
+                self.alpha * (1.0 - ((distance - start) / (end - start)).clamp(0.0, 1.0))
+            }
+            _ => self.alpha,
+        }
+    }
+}
+
+/// Part of a segment in homogeneous clip space inside the near plane and the sides of the view volume,
+/// as the parameters along a → b where it enters and leaves. `None` when it is all outside.
+/// Works on (x, y, z, w) before the perspective division, so points behind the camera never wrap around.
+pub fn clip_range(a: Vector4, b: Vector4) -> Option<(f32, f32)> {
+    // Signed distance to each plane, positive inside: w+x, w-x, w+y, w-y, w+z (near)
+    let distances = |p: Vector4| [p.w + p.x, p.w - p.x, p.w + p.y, p.w - p.y, p.w + p.z];
+    let da = distances(a);
+    let db = distances(b);
+
+    let mut t_enter: f32 = 0.0;
+    let mut t_exit: f32 = 1.0;
+    for plane in 0..5 {
+        if da[plane] < 0.0 && db[plane] < 0.0 {
+            return None;
+        }
+        if da[plane] < 0.0 {
+            t_enter = t_enter.max(da[plane] / (da[plane] - db[plane]));
+        } else if db[plane] < 0.0 {
+            t_exit = t_exit.min(da[plane] / (da[plane] - db[plane]));
+        }
+    }
+
+    (t_enter <= t_exit).then_some((t_enter, t_exit))
+}
+
+/// Point at `t` along a → b in clip space
+pub fn lerp_clip(a: Vector4, b: Vector4, t: f32) -> Vector4 {
+    Vector4::new(
+        a.x + (b.x - a.x) * t,
+        a.y + (b.y - a.y) * t,
+        a.z + (b.z - a.z) * t,
+        a.w + (b.w - a.w) * t,
+    )
+}
+
+/// Clips a segment in homogeneous clip space, see `clip_range`
+fn clip_segment(a: Vector4, b: Vector4) -> Option<(Vector4, Vector4)> {
+    let (t_enter, t_exit) = clip_range(a, b)?;
+    Some((lerp_clip(a, b, t_enter), lerp_clip(a, b, t_exit)))
+}
+
+/// Perspective division and viewport transform: screen x, y with the NDC depth in z
+pub fn to_screen(clip_position: Vector4, viewport_matrix: &Matrix) -> Vector3 {
+    let ndc = Vector4::new(
+        clip_position.x / clip_position.w,
+        clip_position.y / clip_position.w,
+        clip_position.z / clip_position.w,
+        1.0,
+    );
+    let screen_position = multiply_matrix_vector4(viewport_matrix, &ndc);
+    Vector3::new(screen_position.x, screen_position.y, screen_position.z)
+}
+
+/// Model, view and projection stages for a point given in model space
+pub fn to_clip(point: Vector3, uniforms: &Uniforms) -> Vector4 {
+    let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &Vector4::new(point.x, point.y, point.z, 1.0));
+    let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
+    multiply_matrix_vector4(&uniforms.projection_matrix, &view_position)
+}
+
+/// Screen position of a point given in model space, with the NDC depth in z.
+/// `None` when the point is behind the near plane, where the projection would wrap around.
+pub fn project_point(point: Vector3, uniforms: &Uniforms) -> Option<Vector3> {
+    let clip_position = to_clip(point, uniforms);
+    (clip_position.w + clip_position.z >= 0.0).then(|| to_screen(clip_position, &uniforms.viewport_matrix))
+}
+
+/// Draws a segment given in model space through the same model/view/projection/viewport stages as triangles
+pub fn draw_line_3d(framebuffer: &mut Framebuffer, start: Vector3, end: Vector3, uniforms: &Uniforms, style: &LineStyle) {
+    let Some((clip_start, clip_end)) = clip_segment(to_clip(start, uniforms), to_clip(end, uniforms)) else {
+        return;
+    };
+
+    // Fading uses the distance from the camera to the middle of the segment
+    let middle = ((start + end) * 0.5).transform_with(uniforms.model_matrix);
+    let alpha = style.alpha_at(middle.distance_to(uniforms.camera_position));
+    if alpha <= 0.0 {
+        return;
+    }
+
+    framebuffer.draw_line_aa(
+        to_screen(clip_start, &uniforms.viewport_matrix),
+        to_screen(clip_end, &uniforms.viewport_matrix),
+        style.color,
+        alpha,
+        style.width,
+    );
+}
+
+/// Draws connected segments, keeping the dash pattern continuous along the whole polyline
+pub fn draw_polyline_3d(framebuffer: &mut Framebuffer, points: &[Vector3], closed: bool, uniforms: &Uniforms, style: &LineStyle) {
+    if points.len() < 2 {
+        return;
+    }
+
+    let mut segments: Vec<(Vector3, Vector3)> = points.windows(2).map(|pair| (pair[0], pair[1])).collect();
+    if closed {
+        segments.push((points[points.len() - 1], points[0]));
+    }
+
+    let Some((dash, gap)) = style.dash.filter(|(dash, gap)| *dash > 0.0 && *gap > 0.0) else {
+        for (start, end) in segments {
+            draw_line_3d(framebuffer, start, end, uniforms, style);
+        }
+        return;
+    };
+
+    // Distance travelled along the polyline, used to know where each dash starts and ends
+    let period = dash + gap;
+    let mut travelled = 0.0;
+    for (start, end) in segments {
+        let length = start.distance_to(end);
+        let mut position = 0.0;
+
+        while position < length {
+            let phase = (travelled + position) % period;
+            let (drawn, piece) = if phase < dash {
+                (true, dash - phase)
+            } else {
+                (false, period - phase)
+            };
+            let piece_end = (position + piece).min(length);
+            if piece_end <= position {
+                break; // No progress left at this float precision
+            }
+
+            if drawn {
+                let a = start.lerp(end, position / length);
+                let b = start.lerp(end, piece_end / length);
+                draw_line_3d(framebuffer, a, b, uniforms, style);
+            }
+            position = piece_end;
+        }
+
+        travelled += length;
+    }
+}
+
+/// Draws a small three-axis cross centered on `position`, `size` is the length of each arm in world units
+pub fn draw_marker_3d(framebuffer: &mut Framebuffer, position: Vector3, size: f32, uniforms: &Uniforms, style: &LineStyle) {
+    let half = size * 0.5;
+    for axis in [Vector3::new(half, 0.0, 0.0), Vector3::new(0.0, half, 0.0), Vector3::new(0.0, 0.0, half)] {
+        draw_line_3d(framebuffer, position - axis, position + axis, uniforms, style);
+    }
+}
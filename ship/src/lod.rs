@@ -0,0 +1,153 @@
+// lod.rs
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::culling::Bounds;
+use crate::sphere::uv_sphere;
+use crate::vertex::Vertex;
+
+// Fraction of a threshold the projected radius must go past before the level changes,
+// so a body sitting right at a threshold doesn't swap meshes every frame
+const HYSTERESIS: f32 = 0.2;
+
+/// Colors of the LOD debug view, from the finest level to the coarsest
+pub const LOD_COLORS: [Vector3; 4] = [
+    Vector3::new(0.2, 1.0, 0.3), // Green
+    Vector3::new(1.0, 0.9, 0.2), // Yellow
+    Vector3::new(1.0, 0.5, 0.1), // Orange
+    Vector3::new(1.0, 0.2, 0.2), // Red
+];
+
+/// One mesh of a chain, used while the body covers at least `min_radius` pixels
+pub struct LodLevel {
+    pub vertices: Vec<Vertex>,
+    pub bounds: Bounds,
+    pub min_radius: f32,
+}
+
+/// Meshes of the same object at decreasing resolution, finest first.
+/// The last level has no minimum, so there is always one to draw.
+pub struct LodChain {
+    levels: Vec<LodLevel>,
+}
+
+impl LodChain {
+    /// Chain of UV spheres from `(segments, rings, min_radius)` triples, finest first
+    pub fn sphere(radius: f32, resolutions: &[(usize, usize, f32)]) -> Self {
+        let mut levels: Vec<LodLevel> = resolutions
+            .iter()
+            .map(|&(segments, rings, min_radius)| {
+                let vertices = uv_sphere(radius, segments, rings);
+                LodLevel { bounds: Bounds::from_vertices(&vertices), vertices, min_radius }
+            })
+            .collect();
+        if let Some(last) = levels.last_mut() {
+            last.min_radius = 0.0;
+        }
+        LodChain { levels }
+    }
+
+    pub fn vertices(&self, level: usize) -> &[Vertex] {
+        &self.levels[level.min(self.levels.len() - 1)].vertices
+    }
+
+    pub fn bounds(&self, level: usize) -> &Bounds {
+        &self.levels[level.min(self.levels.len() - 1)].bounds
+    }
+
+    /// Level to draw for a body covering `pixel_radius` pixels, given the one drawn last frame.
+    /// A finer level is only picked once the radius is clearly above its threshold, and a coarser one
+    /// once it is clearly below the current threshold.
+    pub fn select(&self, current: usize, pixel_radius: f32) -> usize {
+        let mut level = current.min(self.levels.len() - 1);
+        while level > 0 && pixel_radius > self.levels[level - 1].min_radius * (1.0 + HYSTERESIS) {
+            level -= 1;
+        }
+        while level + 1 < self.levels.len() && pixel_radius < self.levels[level].min_radius * (1.0 - HYSTERESIS) {
+            level += 1;
+        }
+        level
+    }
+}
+
+/// Radius in pixels of a sphere of world `radius` around the origin of the model matrix.
+/// Infinite when the camera is closer to the sphere's center plane than its radius: even with the center
+/// behind the camera, the sphere can fill the view, so it gets the finest level.
+pub fn projected_radius(radius: f32, uniforms: &Uniforms) -> f32 {
+    let view_center = Vector3::zero()
+        .transform_with(uniforms.model_matrix)
+        .transform_with(uniforms.view_matrix);
+    let depth = -view_center.z;
+    if depth < radius {
+        return f32::INFINITY;
+    }
+
+    // Same size estimate as the glow: the projection's vertical scale over the depth, in viewport pixels
+    let half_height = uniforms.viewport_matrix.m5.abs();
+    radius * uniforms.projection_matrix.m5 / depth * half_height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::{create_model_matrix, create_projection_matrix, create_viewport_matrix};
+    use std::f32::consts::FRAC_PI_2;
+
+    // Thresholds of 100 and 20 pixels: a finer level takes more than 120 and 24, a coarser one less than 80 and 16
+    fn chain() -> LodChain {
+        LodChain::sphere(0.5, &[(8, 4, 100.0), (6, 3, 20.0), (4, 2, 0.0)])
+    }
+
+    // Camera at the origin looking down -Z with a 90° field of view over 600 pixels of height
+    fn uniforms_at(center: Vector3) -> Uniforms {
+        Uniforms {
+            model_matrix: create_model_matrix(center, 1.0, Vector3::zero()),
+            view_matrix: Matrix::identity(),
+            projection_matrix: create_projection_matrix(FRAC_PI_2, 1.0, 0.1, 100.0),
+            viewport_matrix: create_viewport_matrix(0.0, 0.0, 600.0, 600.0),
+            camera_position: Vector3::zero(),
+            time: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    #[test]
+    fn finer_level_only_past_the_band() {
+        let chain = chain();
+        assert_eq!(chain.select(1, 110.0), 1);
+        assert_eq!(chain.select(1, 121.0), 0);
+        assert_eq!(chain.select(2, 22.0), 2);
+        assert_eq!(chain.select(2, 130.0), 0);
+    }
+
+    #[test]
+    fn coarser_level_only_past_the_band() {
+        let chain = chain();
+        assert_eq!(chain.select(0, 90.0), 0);
+        assert_eq!(chain.select(0, 79.0), 1);
+        assert_eq!(chain.select(1, 17.0), 1);
+        assert_eq!(chain.select(0, 10.0), 2);
+    }
+
+    #[test]
+    fn last_level_has_no_minimum() {
+        assert_eq!(chain().select(2, 0.0), 2);
+        assert_eq!(chain().select(7, 0.0), 2);
+    }
+
+    #[test]
+    fn projected_radius_shrinks_with_depth() {
+        // tan(45°) = 1, so a radius of 1 at depth 10 covers a tenth of the 300-pixel half height
+        let radius = projected_radius(1.0, &uniforms_at(Vector3::new(0.0, 0.0, -10.0)));
+        assert!((radius - 30.0).abs() < 1e-3);
+        assert!(projected_radius(1.0, &uniforms_at(Vector3::new(0.0, 0.0, -20.0))) < radius);
+    }
+
+    #[test]
+    fn camera_within_the_radius_gets_the_finest_level() {
+        for center in [Vector3::new(0.0, 0.0, -0.5), Vector3::new(0.0, 0.0, 0.5), Vector3::new(3.0, 0.0, -0.9)] {
+            let radius = projected_radius(1.0, &uniforms_at(center));
+            assert_eq!(radius, f32::INFINITY);
+            assert_eq!(chain().select(2, radius), 0);
+        }
+    }
+}
@@ -7,30 +7,98 @@ mod vertex;
 mod camera;
 mod shaders;
 mod light;
+mod atmosphere;
+mod ring;
+mod glow;
+mod line3d;
+mod line;
+mod warp;
+mod ship;
+mod collision;
+mod trajectory;
+mod camera_path;
+mod input;
+mod picking;
+mod font;
+mod hud;
+mod labels;
+mod minimap;
+mod viewport;
+mod sphere;
+mod lod;
+mod culling;
 
 use triangle::triangle;
+use line::line;
+use fragment::Fragment;
 use obj::Obj;
-use framebuffer::Framebuffer;
+use framebuffer::{Framebuffer, BlendMode};
 use raylib::prelude::*;
-use std::thread;
-use std::time::Duration;
 use std::f32::consts::PI;
-use matrix::{create_model_matrix, create_projection_matrix, create_viewport_matrix, multiply_matrix_vector4};
+use matrix::{create_model_matrix, create_projection_matrix, multiply_matrix_vector4};
 use vertex::Vertex;
-use camera::Camera;
-use shaders::{vertex_shader, fragment_shader, mercury_fragment_shader, sun_fragment_shader, earth_fragment_shader, mars_fragment_shader, uranus_fragment_shader, nave_fragment_shader};
+use camera::{Camera, CameraMode, Tracking};
+use shaders::{vertex_shader, fragment_shader, mercury_fragment_shader, sun_fragment_shader, earth_fragment_shader, mars_fragment_shader, uranus_fragment_shader, nave_fragment_shader, atmosphere_fragment_shader, ring_fragment_shader};
 use light::Light;
+use atmosphere::Atmosphere;
+use ring::Rings;
+use glow::Glow;
+use line3d::{LineStyle, draw_marker_3d, draw_polyline_3d};
+use warp::{Warp, WarpDestination};
+use ship::{Attractor, Ship, ShipInput, GRAVITY};
+use collision::{Collider, MeshCollider, Response, resolve, sphere_sphere};
+use trajectory::{Prediction, predict};
+use camera_path::{CameraPath, Keyframe, Playback};
+use input::{Action, Bindings, Context};
+use picking::{Ray, ray_mesh, ray_sphere};
+use hud::{HudInfo, draw_hud};
+use labels::{LabelTarget, draw_labels};
+use minimap::{MapBody, Minimap};
+use viewport::Viewport;
+use sphere::icosphere;
+use lod::{LOD_COLORS, LodChain, projected_radius};
+use culling::{Bounds, CullStats, Frustum};
 
+#[derive(Clone)]
 pub struct Uniforms {
     pub model_matrix: Matrix,
     pub view_matrix: Matrix,
     pub projection_matrix: Matrix,
     pub viewport_matrix: Matrix,
+    pub camera_position: Vector3,
     pub time: f32, // elapsed time in seconds
     pub dt: f32, // delta time in seconds
 }
 
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], light: &Light, planet_type: &str) {
+// Modo de dibujo global: caras rellenas o solo las aristas de cada triángulo
+#[derive(Clone, Copy, PartialEq)]
+enum RenderMode {
+    Solid,
+    Wireframe,
+}
+
+// Rasterization Stage común: rellena el triángulo o, en modo alambre, rasteriza sus tres aristas
+fn rasterize(tri: &[Vertex], light: &Light, mode: RenderMode, uniforms: &Uniforms) -> Vec<Fragment> {
+    match mode {
+        RenderMode::Solid => triangle(&tri[0], &tri[1], &tri[2], light),
+        RenderMode::Wireframe => {
+            let mut fragments = line(&tri[0], &tri[1], uniforms);
+            fragments.extend(line(&tri[1], &tri[2], uniforms));
+            fragments.extend(line(&tri[2], &tri[0], uniforms));
+            fragments
+        }
+    }
+}
+
+// Cómo se dibuja un objeto opaco, además de con su shader
+#[derive(Clone, Copy)]
+struct DrawStyle {
+    mode: RenderMode,
+    selected: bool,        // Marca su silueta para el contorno de selección
+    tint: Option<Vector3>, // Color fijo en lugar del shader, para depurar
+}
+
+fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], light: &Light, planet_type: &str, style: DrawStyle) {
     // Vertex Shader Stage
     let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
     for vertex in vertex_array {
@@ -53,19 +121,22 @@ fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Ve
     // Rasterization Stage
     let mut fragments = Vec::new();
     for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], light));
+        fragments.extend(rasterize(tri, light, style.mode, uniforms));
     }
 
     // Fragment Processing Stage
     for fragment in fragments {      
-        let final_color = match planet_type {
-            "Sun" => sun_fragment_shader(&fragment, uniforms),
-            "Mercury" => mercury_fragment_shader(&fragment, uniforms),
-            "Earth" => earth_fragment_shader(&fragment, uniforms),
-            "Mars" => mars_fragment_shader(&fragment, uniforms),
-            "Uranus" => uranus_fragment_shader(&fragment, uniforms),
-            "Nave" => nave_fragment_shader(&fragment, uniforms),
-            _ => fragment_shader(&fragment, uniforms), // Default to simple shader
+        let final_color = match (style.tint, planet_type) {
+            // Vista de depuración: un color fijo, con algo de luz ambiente para que se vea la forma.
+            // El color del fragmento es el gris base (0.5) por la intensidad de la luz
+            (Some(color), _) => color * (0.3 + 0.7 * (fragment.color.x * 2.0).clamp(0.0, 1.0)),
+            (None, "Sun") => sun_fragment_shader(&fragment, uniforms),
+            (None, "Mercury") => mercury_fragment_shader(&fragment, uniforms),
+            (None, "Earth") => earth_fragment_shader(&fragment, uniforms),
+            (None, "Mars") => mars_fragment_shader(&fragment, uniforms),
+            (None, "Uranus") => uranus_fragment_shader(&fragment, uniforms),
+            (None, "Nave") => nave_fragment_shader(&fragment, uniforms),
+            (None, _) => fragment_shader(&fragment, uniforms), // Default to simple shader
         };
         
         framebuffer.point(
@@ -74,77 +145,351 @@ fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Ve
             final_color, //poner fragment.color si no se quiere nada de shading 
             fragment.depth,
         );
+
+        // La silueta del objeto seleccionado se usa después para dibujar su contorno
+        if style.selected {
+            framebuffer.mark_selected(fragment.position.x as i32, fragment.position.y as i32);
+        }
+    }
+}
+
+// Dibuja el cascarón de atmósfera sumando la luz dispersada sobre lo ya renderizado
+fn render_atmosphere(framebuffer: &mut Framebuffer, uniforms: &Uniforms, shell_vertices: &[Vertex], light: &Light, atmosphere: &Atmosphere, mode: RenderMode) {
+    // Cámara y luz en el espacio del modelo, una vez por cascarón: para descartar caras traseras y para el shader
+    let inverse_model = uniforms.model_matrix.inverted();
+    let eye = uniforms.camera_position.transform_with(inverse_model);
+    let light_position = light.position.transform_with(inverse_model);
+    let eye_inside = eye.length() < atmosphere.radius;
+
+    // Vertex Shader Stage
+    let mut transformed_vertices = Vec::with_capacity(shell_vertices.len());
+    for vertex in shell_vertices {
+        transformed_vertices.push(vertex_shader(vertex, uniforms));
+    }
+
+    // Primitive Assembly Stage
+    // Solo las caras que miran a la cámara, para no sumar la dispersión dos veces.
+    // Desde dentro del cascarón solo se ven las caras traseras, así que se dejan todas.
+    let mut triangles = Vec::new();
+    for tri in transformed_vertices.chunks_exact(3) {
+        let center = (tri[0].position + tri[1].position + tri[2].position) / 3.0;
+        if eye_inside || center.dot(eye - center) > 0.0 {
+            triangles.push(tri);
+        }
+    }
+
+    // Rasterization Stage
+    let mut fragments = Vec::new();
+    for tri in triangles {
+        fragments.extend(rasterize(tri, light, mode, uniforms));
+    }
+
+    // Fragment Processing Stage
+    for fragment in fragments {
+        let scattered = atmosphere_fragment_shader(&fragment, atmosphere, eye, light_position);
+
+        framebuffer.blend_point(
+            fragment.position.x as i32,
+            fragment.position.y as i32,
+            scattered,
+            1.0,
+            fragment.depth,
+            BlendMode::Additive,
+        );
+    }
+}
+
+// Dibuja los anillos de un planeta mezclándolos con lo ya renderizado según su transparencia
+fn render_rings(framebuffer: &mut Framebuffer, uniforms: &Uniforms, ring_vertices: &[Vertex], light: &Light, rings: &Rings, mode: RenderMode) {
+    // Cámara y luz en el espacio del modelo, una vez por anillo en lugar de por fragmento
+    let inverse_model = uniforms.model_matrix.inverted();
+    let eye = uniforms.camera_position.transform_with(inverse_model);
+    let light_position = light.position.transform_with(inverse_model);
+
+    // Vertex Shader Stage
+    let mut transformed_vertices = Vec::with_capacity(ring_vertices.len());
+    for vertex in ring_vertices {
+        transformed_vertices.push(vertex_shader(vertex, uniforms));
+    }
+
+    // Rasterization Stage (el anillo se ve por ambas caras, no se descarta ninguna)
+    let mut fragments = Vec::new();
+    for tri in transformed_vertices.chunks_exact(3) {
+        fragments.extend(rasterize(tri, light, mode, uniforms));
+    }
+
+    // Fragment Processing Stage
+    for fragment in fragments {
+        let color = ring_fragment_shader(&fragment, rings, eye, light_position);
+        if color.w <= 0.0 {
+            continue;
+        }
+
+        framebuffer.blend_point(
+            fragment.position.x as i32,
+            fragment.position.y as i32,
+            Vector3::new(color.x, color.y, color.z),
+            color.w,
+            fragment.depth,
+            BlendMode::Alpha,
+        );
+    }
+}
+
+// Dibuja el halo de un cuerpo brillante como un disco de frente a la cámara
+fn render_glow(framebuffer: &mut Framebuffer, uniforms: &Uniforms, glow: &Glow) {
+    // Centro del cuerpo en espacio de vista; la cámara mira hacia -z
+    let view_center = Vector3::zero()
+        .transform_with(uniforms.model_matrix)
+        .transform_with(uniforms.view_matrix);
+    if view_center.z >= 0.0 {
+        return;
+    }
+
+    // Radio del halo en el mundo y su tamaño proyectado en píxeles
+    let model = &uniforms.model_matrix;
+    let world_radius = glow.radius * Vector3::new(model.m0, model.m1, model.m2).length();
+    let half_height = uniforms.viewport_matrix.m5.abs();
+    let pixel_radius = world_radius * uniforms.projection_matrix.m5 / -view_center.z * half_height;
+
+    // Centro en pantalla, con la profundidad del cuerpo para que lo opaco de delante lo tape
+    let clip_position = multiply_matrix_vector4(
+        &uniforms.projection_matrix,
+        &Vector4::new(view_center.x, view_center.y, view_center.z, 1.0),
+    );
+    let ndc = Vector4::new(
+        clip_position.x / clip_position.w,
+        clip_position.y / clip_position.w,
+        clip_position.z / clip_position.w,
+        1.0,
+    );
+    let screen = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc);
+
+    let min_x = ((screen.x - pixel_radius) as i32).max(0);
+    let max_x = ((screen.x + pixel_radius) as i32).min(framebuffer.width - 1);
+    let min_y = ((screen.y - pixel_radius) as i32).max(0);
+    let max_y = ((screen.y + pixel_radius) as i32).min(framebuffer.height - 1);
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = x as f32 + 0.5 - screen.x;
+            let dy = y as f32 + 0.5 - screen.y;
+            let alpha = glow.alpha((dx * dx + dy * dy).sqrt() / pixel_radius);
+            if alpha > 0.0 {
+                framebuffer.blend_point(x, y, glow.color * alpha, alpha, screen.z, BlendMode::Premultiplied);
+            }
+        }
+    }
+}
+
+// Objetos transparentes: se dibujan después de todo lo opaco
+enum TransparentObject<'a> {
+    Rings(&'a Rings, &'a [Vertex]),
+    Atmosphere(&'a Atmosphere, &'a [Vertex]),
+    Glow(&'a Glow),
+}
+
+struct TransparentDraw<'a> {
+    uniforms: Uniforms,
+    object: TransparentObject<'a>,
+    distance: f32, // Distancia de la cámara al centro del objeto, para ordenar
+}
+
+impl<'a> TransparentDraw<'a> {
+    fn new(uniforms: Uniforms, object: TransparentObject<'a>) -> Self {
+        let center = Vector3::zero().transform_with(uniforms.model_matrix);
+        let distance = center.distance_to(uniforms.camera_position);
+        TransparentDraw { uniforms, object, distance }
+    }
+}
+
+// Pase transparente: ordena de atrás hacia adelante y mezcla cada objeto sobre lo ya dibujado.
+// Todos prueban profundidad contra lo opaco pero no la escriben.
+fn render_transparent(framebuffer: &mut Framebuffer, draws: &mut [TransparentDraw], light: &Light, mode: RenderMode) {
+    draws.sort_by(|a, b| b.distance.total_cmp(&a.distance));
+
+    for draw in draws.iter() {
+        match draw.object {
+            TransparentObject::Rings(rings, vertices) => render_rings(framebuffer, &draw.uniforms, vertices, light, rings, mode),
+            TransparentObject::Atmosphere(atmosphere, vertices) => render_atmosphere(framebuffer, &draw.uniforms, vertices, light, atmosphere, mode),
+            TransparentObject::Glow(glow) => render_glow(framebuffer, &draw.uniforms, glow),
+        }
     }
 }
 
 // Función para dibujar una órbita circular en 3D
-fn draw_orbit_3d(framebuffer: &mut Framebuffer, orbit_radius: f32, orbit_color: Color, view_matrix: &Matrix, projection_matrix: &Matrix, viewport_matrix: &Matrix) {
+fn draw_orbit_3d(framebuffer: &mut Framebuffer, orbit_radius: f32, uniforms: &Uniforms, style: &LineStyle) {
     let segments = 128; // Aumentamos el número de segmentos para una línea más suave
     let angle_increment = 2.0 * PI / segments as f32;
-    
-    // Crear un vértice temporal para transformar puntos
-    let mut prev_x = 0;
-    let mut prev_y = 0;
-    let mut first_point = true;
-    
-    // Guardar el primer punto para cerrar el círculo
-    let mut first_x = 0;
-    let mut first_y = 0;
-    
-    for i in 0..segments {
-        let angle = i as f32 * angle_increment;
-        
-        // Punto en el círculo (en el plano XZ, Y=0)
-        let x = angle.cos() * orbit_radius;
-        let y = 0.0; // En el plano XZ
-        let z = angle.sin() * orbit_radius;
-        
-        // Transformar el punto a coordenadas de pantalla
-        let position_vec4 = Vector4::new(x, y, z, 1.0);
-        
-        // Aplicar transformaciones
-        let view_position = multiply_matrix_vector4(view_matrix, &position_vec4);
-        let clip_position = multiply_matrix_vector4(projection_matrix, &view_position);
-        
-        // Perspectiva division
-        let ndc = if clip_position.w != 0.0 {
-            Vector3::new(
-                clip_position.x / clip_position.w,
-                clip_position.y / clip_position.w,
-                clip_position.z / clip_position.w,
-            )
-        } else {
-            Vector3::new(clip_position.x, clip_position.y, clip_position.z)
-        };
-        
-        // Aplicar matriz de viewport
-        let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-        let screen_position = multiply_matrix_vector4(viewport_matrix, &ndc_vec4);
-        
-        let screen_x = screen_position.x as i32;
-        let screen_y = screen_position.y as i32;
-        
-        // Guardar el primer punto
-        if i == 0 {
-            first_x = screen_x;
-            first_y = screen_y;
+
+    // Puntos del círculo en el plano XZ (Y=0)
+    let points: Vec<Vector3> = (0..segments)
+        .map(|i| {
+            let angle = i as f32 * angle_increment;
+            Vector3::new(angle.cos() * orbit_radius, 0.0, angle.sin() * orbit_radius)
+        })
+        .collect();
+
+    // Línea cerrada que pasa por recorte, viewport y prueba de profundidad como los triángulos
+    draw_polyline_3d(framebuffer, &points, true, uniforms, style);
+}
+
+// Dibuja la trayectoria prevista de la nave y marca periapsis, apoapsis e impacto
+fn draw_prediction(framebuffer: &mut Framebuffer, prediction: &Prediction, uniforms: &Uniforms) {
+    let path_style = LineStyle::solid(Vector3::new(0.3, 1.0, 0.5), 0.6, 1.5);
+    draw_polyline_3d(framebuffer, &prediction.points, false, uniforms, &path_style);
+
+    let markers = [
+        (prediction.periapsis, Vector3::new(0.3, 0.8, 1.0)), // Celeste
+        (prediction.apoapsis, Vector3::new(1.0, 0.7, 0.2)),  // Naranja
+        (prediction.impact, Vector3::new(1.0, 0.2, 0.2)),    // Rojo
+    ];
+    for (position, color) in markers {
+        if let Some(position) = position {
+            draw_marker_3d(framebuffer, position, 1.0, uniforms, &LineStyle::solid(color, 1.0, 2.0));
         }
-        
-        // Dibujar línea desde el punto anterior al actual
-        if !first_point {
-            // Dibujar la línea con una profundidad mayor (más lejos) que los planetas
-            framebuffer.draw_line_with_depth(prev_x, prev_y, screen_x, screen_y, orbit_color, 1000.0);
+    }
+}
+
+// Todo lo que se dibuja en cada vista, igual para todas las cámaras
+struct Scene<'a> {
+    celestial_bodies: &'a [CelestialBody],
+    sphere_lods: &'a LodChain, // Malla de los cuerpos celestes en varios niveles de detalle
+    lod_colors: bool,          // Pintar cada cuerpo según el nivel de detalle que usa
+    atmosphere_shells: &'a [Option<Vec<Vertex>>],
+    ring_meshes: &'a [Option<Vec<Vertex>>],
+    ship_vertices: &'a [Vertex],
+    ship_bounds: Bounds,
+    ship_matrix: Matrix,
+    prediction: Option<&'a Prediction>,
+    light: &'a Light,
+    selection: Option<Focus>,
+    mode: RenderMode,
+    time: f32,
+    dt: f32,
+}
+
+// Matrices para dibujar un objeto con `model_matrix` desde `camera` dentro de `viewport`
+fn view_uniforms(camera: &Camera, viewport: &Viewport, model_matrix: Matrix, time: f32, dt: f32) -> Uniforms {
+    Uniforms {
+        model_matrix,
+        view_matrix: camera.get_view_matrix(),
+        projection_matrix: create_projection_matrix(camera.fov, viewport.aspect(), 0.1, 100.0),
+        viewport_matrix: viewport.matrix(),
+        camera_position: camera.eye,
+        time,
+        dt,
+    }
+}
+
+// Dibuja la escena vista por `camera` dentro de `viewport`, que se borra antes y recorta todo lo demás.
+// `lods` guarda el nivel de detalle de cada cuerpo en esta vista de un cuadro al siguiente.
+// Las mallas opacas fuera del campo de visión no se dibujan y se cuentan en `stats`.
+// Devuelve las matrices con la identidad como modelo, para dibujar encima en coordenadas del mundo
+fn render_view(framebuffer: &mut Framebuffer, scene: &Scene, camera: &Camera, viewport: &Viewport, lods: &mut [usize], stats: &mut CullStats) -> Uniforms {
+    viewport.begin(framebuffer);
+    let (time, dt) = (scene.time, scene.dt);
+    let world_uniforms = view_uniforms(camera, viewport, Matrix::identity(), time, dt);
+    let frustum = Frustum::new(world_uniforms.view_matrix, world_uniforms.projection_matrix);
+
+    // Anillos, atmósferas y halos se guardan para el pase transparente
+    let mut transparent_draws = Vec::new();
+
+    // Render each celestial body FIRST
+    for (index, body) in scene.celestial_bodies.iter().enumerate() {
+        // Posición en la órbita y giro sobre su eje
+        let translation = body.position(time);
+        let rotation = body.rotation + Vector3::new(0.0, dt * body.rotation_speed, 0.0);
+
+        // Set color for the body
+        framebuffer.set_current_color(body.color);
+
+        let uniforms = view_uniforms(camera, viewport, create_model_matrix(translation, body.scale, rotation), time, dt);
+
+        // Nivel de detalle según el radio del cuerpo en pantalla
+        let chain = scene.sphere_lods;
+        lods[index] = chain.select(lods[index], projected_radius(body.radius(), &uniforms));
+        let tint = scene.lod_colors.then(|| LOD_COLORS[lods[index].min(LOD_COLORS.len() - 1)]);
+        let style = DrawStyle { mode: scene.mode, selected: scene.selection == Some(Focus::Body(index)), tint };
+        if frustum.intersects(chain.bounds(lods[index]), uniforms.model_matrix) {
+            stats.drawn += 1;
+            render(framebuffer, &uniforms, chain.vertices(lods[index]), scene.light, &body.name, style);
         } else {
-            first_point = false;
+            stats.culled += 1;
+        }
+
+        if let (Some(rings), Some(ring_mesh)) = (&body.rings, &scene.ring_meshes[index]) {
+            // Los anillos comparten posición y escala con el planeta, pero con su propia inclinación
+            let ring_uniforms = view_uniforms(camera, viewport, create_model_matrix(translation, body.scale, rings.tilt), time, dt);
+            transparent_draws.push(TransparentDraw::new(ring_uniforms, TransparentObject::Rings(rings, ring_mesh)));
+        }
+
+        if let Some(glow) = &body.glow {
+            transparent_draws.push(TransparentDraw::new(uniforms.clone(), TransparentObject::Glow(glow)));
+        }
+
+        if let (Some(atmosphere), Some(shell)) = (&body.atmosphere, &scene.atmosphere_shells[index]) {
+            transparent_draws.push(TransparentDraw::new(uniforms, TransparentObject::Atmosphere(atmosphere, shell)));
         }
-        
-        prev_x = screen_x;
-        prev_y = screen_y;
     }
-    
-    // Cerrar el círculo conectando el último punto con el primero
-    if segments > 0 {
-        framebuffer.draw_line_with_depth(prev_x, prev_y, first_x, first_y, orbit_color, 1000.0);
+
+    // Renderizar la nave con su shader específico
+    let nave_uniforms = view_uniforms(camera, viewport, scene.ship_matrix, time, dt);
+    let style = DrawStyle { mode: scene.mode, selected: scene.selection == Some(Focus::Ship), tint: None };
+    if frustum.intersects(&scene.ship_bounds, scene.ship_matrix) {
+        stats.drawn += 1;
+        render(framebuffer, &nave_uniforms, scene.ship_vertices, scene.light, "Nave", style);
+    } else {
+        stats.culled += 1;
+    }
+
+    // Dibujar las órbitas de los planetas en blanco AFTER rendering the opaque objects
+    let orbit_uniforms = world_uniforms; // Las órbitas ya están en coordenadas del mundo
+    let orbit_style = LineStyle {
+        fade_distance: Some((80.0, 220.0)), // Las partes lejanas se desvanecen
+        ..LineStyle::solid(Vector3::new(1.0, 1.0, 1.0), 50.0 / 255.0, 1.5) // Blanco con menor transparencia (más discreto)
+    };
+    for body in scene.celestial_bodies {
+        if body.name != "Sun" {
+            draw_orbit_3d(framebuffer, body.orbit_radius, &orbit_uniforms, &orbit_style);
+        }
+    }
+
+    // Trayectoria prevista de la nave
+    if let Some(prediction) = scene.prediction {
+        draw_prediction(framebuffer, prediction, &orbit_uniforms);
+    }
+
+    // Pase transparente: anillos, atmósferas y halos de atrás hacia adelante
+    render_transparent(framebuffer, &mut transparent_draws, scene.light, scene.mode);
+
+    orbit_uniforms
+}
+
+// Cámara que dibuja cada vista: la que se controla o la que mira el sistema entero
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Main,
+    Overview,
+}
+
+impl View {
+    fn camera<'a>(self, main: &'a Camera, overview: &'a Camera) -> &'a Camera {
+        match self {
+            View::Main => main,
+            View::Overview => overview,
+        }
+    }
+
+    // Rectángulos de la pantalla y sus cámaras; la principal siempre va primero.
+    // Con la pantalla dividida, la principal (por ejemplo en cabina) a la izquierda y el sistema a la derecha
+    fn layout(screen: Viewport, split: bool) -> Vec<(Viewport, View)> {
+        if split {
+            let (left, right) = screen.split_vertical();
+            vec![(left, View::Main), (right, View::Overview)]
+        } else {
+            vec![(screen, View::Main)]
+        }
     }
 }
 
@@ -158,82 +503,200 @@ struct CelestialBody {
     orbit_speed: f32,
     rotation_speed: f32,
     color: Color,
+    atmosphere: Option<Atmosphere>,
+    rings: Option<Rings>,
+    glow: Option<Glow>,
+}
+
+impl CelestialBody {
+    // Posición del cuerpo en su órbita en el instante `time` (el sol se queda en su lugar)
+    fn position(&self, time: f32) -> Vector3 {
+        if self.name == "Sun" {
+            return self.translation;
+        }
+        Vector3::new(
+            (time * self.orbit_speed).cos() * self.orbit_radius,
+            self.translation.y,
+            (time * self.orbit_speed).sin() * self.orbit_radius,
+        )
+    }
+
+    // Radio del cuerpo en el mundo (la esfera del modelo tiene radio 0.5)
+    fn radius(&self) -> f32 {
+        self.scale * 0.5
+    }
+
+    // Velocidad del cuerpo en su órbita, para que empuje lo que toca
+    fn velocity(&self, time: f32) -> Vector3 {
+        if self.name == "Sun" {
+            return Vector3::zero();
+        }
+        let angle = time * self.orbit_speed;
+        Vector3::new(-angle.sin(), 0.0, angle.cos()) * (self.orbit_radius * self.orbit_speed)
+    }
+
+    // Masa para la gravedad: todos los cuerpos tienen la misma densidad
+    fn mass(&self) -> f32 {
+        self.scale.powi(3)
+    }
 }
 
-// Función para verificar colisión entre dos esferas
-fn check_collision(pos1: Vector3, radius1: f32, pos2: Vector3, radius2: f32) -> bool {
-    let distance = ((pos1.x - pos2.x).powi(2) + (pos1.y - pos2.y).powi(2) + (pos1.z - pos2.z).powi(2)).sqrt();
-    distance < (radius1 + radius2)
+// Punto de vista para encuadrar un cuerpo: del lado iluminado y un poco por encima,
+// a una distancia proporcional a su radio para que ocupe parte de la pantalla
+fn body_framing(body: &CelestialBody, time: f32, fov: f32) -> (Vector3, Vector3, Vector3) {
+    let center = body.position(time);
+    let outward = Vector3::new(center.x, 0.0, center.z);
+    let outward = if outward.length() > 1e-3 { outward.normalized() } else { Vector3::new(0.0, 0.0, 1.0) };
+    let direction = (outward + Vector3::new(0.0, 0.6, 0.0)).normalized();
+    let distance = body.radius() / (fov / 2.0).sin() * 2.5;
+
+    (center + direction * distance, center, Vector3::new(0.0, 1.0, 0.0))
 }
 
-// Función para evitar colisiones
-fn avoid_collision(camera_pos: Vector3, target_pos: Vector3, celestial_bodies: &[CelestialBody], time: f32) -> (Vector3, Vector3) {
-    let mut new_camera_pos = camera_pos;
-    let mut new_target_pos = target_pos;
-    
-    // Verificar colisiones con cada cuerpo celeste
+// Vista desde atrás y arriba de la nave, donde la dejaría la cámara de persecución
+fn ship_framing(ship: &Ship, chase_offset: Vector3) -> (Vector3, Vector3, Vector3) {
+    let eye = chase_offset.transform_with(ship.model_matrix());
+    (eye, ship.position, Vector3::new(0.0, 1.0, 0.0).rotate_by(ship.orientation))
+}
+
+// Objeto bajo el rayo: el más cercano entre los cuerpos celestes y la nave
+fn pick(ray: Ray, celestial_bodies: &[CelestialBody], time: f32, ship_mesh: &MeshCollider, ship_matrix: Matrix) -> Option<Focus> {
+    let bodies = celestial_bodies
+        .iter()
+        .enumerate()
+        .filter_map(|(index, body)| ray_sphere(ray, body.position(time), body.radius()).map(|distance| (Focus::Body(index), distance)));
+    let ship = ray_mesh(ray, ship_mesh, ship_matrix).map(|distance| (Focus::Ship, distance));
+
+    bodies.chain(ship).min_by(|a, b| a.1.total_cmp(&b.1)).map(|(focus, _)| focus)
+}
+
+// Cuerpos como los muestra el minimapa, en sus posiciones del instante `time`
+fn map_bodies(celestial_bodies: &[CelestialBody], time: f32) -> Vec<MapBody> {
+    celestial_bodies
+        .iter()
+        .map(|body| MapBody {
+            position: body.position(time),
+            radius: body.radius(),
+            orbit_radius: body.orbit_radius,
+            color: Vector3::new(body.color.r as f32, body.color.g as f32, body.color.b as f32) / 255.0,
+        })
+        .collect()
+}
+
+// Cuerpos que atraen a la nave, en sus posiciones del instante `time`
+fn attractors(celestial_bodies: &[CelestialBody], time: f32) -> Vec<Attractor> {
+    celestial_bodies
+        .iter()
+        .map(|body| Attractor {
+            position: body.position(time),
+            mass: body.mass(),
+            radius: body.radius(),
+        })
+        .collect()
+}
+
+// Objeto que siguen los modos de seguimiento de la cámara
+#[derive(Clone, Copy, PartialEq)]
+enum Focus {
+    Body(usize), // Índice en la lista de cuerpos celestes
+    Ship,
+}
+
+impl Focus {
+    fn name(self, celestial_bodies: &[CelestialBody]) -> &str {
+        match self {
+            Focus::Body(index) => &celestial_bodies[index].name,
+            Focus::Ship => "Ship",
+        }
+    }
+
+    // Siguiente objeto al recorrer los cuerpos y luego la nave
+    fn next(self, body_count: usize) -> Focus {
+        match self {
+            Focus::Body(index) if index + 1 < body_count => Focus::Body(index + 1),
+            Focus::Body(_) => Focus::Ship,
+            Focus::Ship => Focus::Body(0),
+        }
+    }
+}
+
+// Saca la cámara de los cuerpos celestes. La cámara de persecución además ajusta su velocidad.
+// La cámara se mueve en tiempo real y los cuerpos en tiempo simulado: `sim_speed` pasa la velocidad
+// de los cuerpos a segundos reales, así en pausa están quietos para la cámara
+fn collide_camera(camera: &mut Camera, celestial_bodies: &[CelestialBody], time: f32, sim_speed: f32) {
+    let mut eye = camera.eye;
+    let mut velocity = if camera.mode == CameraMode::Chase { camera.chase_velocity } else { Vector3::zero() };
+
     for body in celestial_bodies {
-        let body_pos = if body.name != "Sun" {
-            // Calcular posición actual del planeta en su órbita
-            let x = (time * body.orbit_speed).cos() * body.orbit_radius;
-            let z = (time * body.orbit_speed).sin() * body.orbit_radius;
-            Vector3::new(x, 0.0, z)
-        } else {
-            body.translation // Posición del sol
-        };
-        
-        // Calcular radios efectivos (considerando el tamaño del cuerpo)
-        let camera_radius = 2.0; // Radio de colisión de la cámara
-        let body_radius = body.scale * 0.8; // Radio de colisión del cuerpo celeste
-        
-        // Verificar si hay colisión con la cámara
-        if check_collision(new_camera_pos, camera_radius, body_pos, body_radius) {
-            // Calcular vector de separación
-            let diff_x = new_camera_pos.x - body_pos.x;
-            let diff_y = new_camera_pos.y - body_pos.y;
-            let diff_z = new_camera_pos.z - body_pos.z;
-            let distance = (diff_x.powi(2) + diff_y.powi(2) + diff_z.powi(2)).sqrt();
-            
-            if distance > 0.0 {
-                // Normalizar el vector de separación
-                let norm_x = diff_x / distance;
-                let norm_y = diff_y / distance;
-                let norm_z = diff_z / distance;
-                
-                // Calcular nueva posición para evitar la colisión
-                let min_distance = body_radius + camera_radius;
-                new_camera_pos.x = body_pos.x + norm_x * min_distance;
-                new_camera_pos.y = body_pos.y + norm_y * min_distance;
-                new_camera_pos.z = body_pos.z + norm_z * min_distance;
-            }
+        if let Some(contact) = sphere_sphere(eye, CAMERA_RADIUS, body.position(time), body.radius()) {
+            resolve(&mut eye, &mut velocity, body.velocity(time) * sim_speed, &contact, CAMERA_RESPONSE);
         }
-        
-        // Verificar si hay colisión con el punto de mira
-        if check_collision(new_target_pos, camera_radius, body_pos, body_radius) {
-            // Calcular vector de separación
-            let diff_x = new_target_pos.x - body_pos.x;
-            let diff_y = new_target_pos.y - body_pos.y;
-            let diff_z = new_target_pos.z - body_pos.z;
-            let distance = (diff_x.powi(2) + diff_y.powi(2) + diff_z.powi(2)).sqrt();
-            
-            if distance > 0.0 {
-                // Normalizar el vector de separación
-                let norm_x = diff_x / distance;
-                let norm_y = diff_y / distance;
-                let norm_z = diff_z / distance;
-                
-                // Calcular nueva posición para evitar la colisión
-                let min_distance = body_radius + camera_radius;
-                new_target_pos.x = body_pos.x + norm_x * min_distance;
-                new_target_pos.y = body_pos.y + norm_y * min_distance;
-                new_target_pos.z = body_pos.z + norm_z * min_distance;
-            }
+    }
+
+    if camera.mode == CameraMode::Chase {
+        camera.chase_velocity = velocity;
+    }
+    camera.set_position(eye, camera.target);
+}
+
+// Choques de la nave contra los cuerpos celestes usando la malla de nave.obj
+fn collide_ship(ship: &mut Ship, collider: &Collider, response: Response, celestial_bodies: &[CelestialBody], time: f32) {
+    for body in celestial_bodies {
+        if let Some(contact) = collider.contact_with_sphere(ship.model_matrix(), body.position(time), body.radius()) {
+            resolve(&mut ship.position, &mut ship.velocity, body.velocity(time), &contact, response);
         }
     }
-    
-    (new_camera_pos, new_target_pos)
 }
 
+// Radio de la esfera de colisión de la cámara, mayor que el plano cercano para no ver el interior de los planetas
+const CAMERA_RADIUS: f32 = 0.3;
+// La cámara se desliza sobre la superficie de los cuerpos
+const CAMERA_RESPONSE: Response = Response::Slide { friction: 0.0 };
+
+// Segundos de trayectoria prevista y paso de la integración
+const PREDICTION_SECONDS: f32 = 40.0;
+const PREDICTION_STEP: f32 = 0.1;
+
+// Archivo de los recorridos de cámara y cuadros por segundo al exportarlos
+const CAMERA_PATH_FILE: &str = "./camera_path.txt";
+const EXPORT_FPS: f32 = 30.0;
+
+// Asignación de teclas, botones del ratón y del control a cada acción
+const CONTROLS_FILE: &str = "./controls.txt";
+
+// Límites de la velocidad de la simulación, en segundos simulados por segundo real
+const MIN_TIME_SCALE: f32 = 1.0 / 16.0;
+const MAX_TIME_SCALE: f32 = 64.0;
+
+// Color y grosor en píxeles del contorno del objeto seleccionado
+const SELECTION_COLOR: Vector3 = Vector3::new(1.0, 0.8, 0.2);
+const SELECTION_THICKNESS: i32 = 2;
+
+// Lado en píxeles del minimapa, separación del borde de la ventana y unidades del mundo del centro al borde
+const MINIMAP_SIZE: i32 = 240;
+const MINIMAP_MARGIN: i32 = 10;
+const MINIMAP_EXTENT: f32 = 55.0;
+
+// Niveles de detalle de los cuerpos celestes, del más fino al más grueso: divisiones alrededor del eje,
+// de polo a polo y radio mínimo en píxeles para usarlo
+const SPHERE_LODS: [(usize, usize, f32); 4] = [(64, 32, 160.0), (32, 16, 60.0), (16, 8, 20.0), (8, 4, 0.0)];
+// Subdivisiones de la icoesfera de los cascarones de atmósfera
+const SHELL_SUBDIVISIONS: usize = 3;
+
+// Posición de la cámara que mira todo el sistema con la pantalla dividida
+const OVERVIEW_EYE: Vector3 = Vector3::new(0.0, 55.0, 60.0);
+
+// Cuadros por segundo objetivo; el movimiento usa dt, así que no depende de este valor
+const TARGET_FPS: u32 = 60;
+// Índices de los destinos de warp en la lista de cuerpos celestes
+const EARTH: usize = 2;
+const MARS: usize = 3;
+const URANUS: usize = 4;
+// Radio de la órbita circular alrededor del sol donde empieza la nave
+const SHIP_START_ORBIT: f32 = 30.0;
+// Duración de los viajes entre planetas, en segundos
+const WARP_DURATION: f32 = 2.5;
+
 fn main() {
     let window_width = 1300;
     let window_height = 900;
@@ -244,7 +707,42 @@ fn main() {
         .log_level(TraceLogLevel::LOG_WARNING)
         .build();
 
+    // Exportación de cuadros sin ventana: cargo run -- --render camera_path.txt carpeta_de_salida
+    let args: Vec<String> = std::env::args().collect();
+    let render_job = match args.as_slice() {
+        [_, flag, path_file, output_dir] if flag == "--render" => Some((path_file.clone(), output_dir.clone())),
+        _ => None,
+    };
+
+    if render_job.is_some() {
+        // Se oculta la ventana y se renderiza tan rápido como se pueda, con un paso de tiempo fijo
+        window.set_window_state(window.get_window_state().set_window_hidden(true));
+        window.set_target_fps(0);
+    } else {
+        // Limitador de cuadros: raylib espera lo necesario al terminar cada cuadro
+        window.set_target_fps(TARGET_FPS);
+    }
+
     let mut framebuffer = Framebuffer::new(window_width, window_height);
+
+    // Controles: los valores por defecto, sobrescritos por lo que diga el archivo
+    let bindings = match Bindings::load(CONTROLS_FILE) {
+        Ok(bindings) => bindings,
+        Err(error) => {
+            eprintln!("Failed to load controls, using the defaults: {error}");
+            Bindings::defaults()
+        }
+    };
+    for conflict in bindings.conflicts() {
+        eprintln!("Binding conflict: {} is bound to both {} and {}", conflict.input.name(), conflict.first.name(), conflict.second.name());
+    }
+    let mut show_help = false;
+    let mut show_hud = true;
+    let mut show_labels = true;
+    let mut show_minimap = true;
+    let mut split_view = false;
+    let screen = Viewport::full(window_width, window_height);
+    let minimap = Minimap::bottom_right(window_width, window_height, MINIMAP_SIZE, MINIMAP_MARGIN, MINIMAP_EXTENT);
     
     // Posición inicial de la cámara
     let initial_camera_pos = Vector3::new(0.0, 20.0, 75.0);
@@ -258,15 +756,18 @@ fn main() {
         initial_camera_up,
     );
 
+    // Cámara fija que mira todo el sistema, para la mitad derecha de la pantalla dividida
+    let overview_camera = Camera::new(OVERVIEW_EYE, Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+
     // Light
     let light = Light::new(Vector3::new(0.0, 0.0, 0.0)); // fix light
 
-    let obj = Obj::load("./models/sphere.obj").expect("Failed to load obj");
-    let vertex_array = obj.get_vertex_array();
 
     // Cargar la nave espacial
     let nave_obj = Obj::load("./models/nave.obj").expect("Failed to load nave.obj");
     let nave_vertex_array = nave_obj.get_vertex_array();
+    let ship_mesh = MeshCollider::from_vertices(&nave_vertex_array);
+    let ship_collider = Collider::Mesh(ship_mesh.clone());
 
     framebuffer.set_background_color(Color::new(25, 25, 75, 255));
 
@@ -279,6 +780,9 @@ fn main() {
         orbit_speed: 0.0,
         rotation_speed: 0.5, // Rotates on its axis
         color: Color::new(255, 255, 0, 255), // Yellow for sun
+        atmosphere: None,
+        rings: None,
+        glow: Some(Glow::sun()),
     };
 
     let mercury = CelestialBody {
@@ -290,6 +794,9 @@ fn main() {
         orbit_speed: 0.8, // Orbital speed
         rotation_speed: 2.0, // Rotation speed on its axis
         color: Color::new(169, 169, 169, 255), // Gray for Mercury
+        atmosphere: None,
+        rings: None,
+        glow: None,
     };
 
     let earth = CelestialBody {
@@ -301,6 +808,9 @@ fn main() {
         orbit_speed: 0.5, // Orbital speed
         rotation_speed: 1.5, // Rotation speed on its axis
         color: Color::new(0, 100, 200, 255), // Blue for Earth
+        atmosphere: Some(Atmosphere::earth()),
+        rings: None,
+        glow: None,
     };
 
     let mars = CelestialBody {
@@ -312,6 +822,9 @@ fn main() {
         orbit_speed: 0.3, // Orbital speed
         rotation_speed: 1.2, // Rotation speed on its axis
         color: Color::new(205, 92, 92, 255), // Red for Mars
+        atmosphere: None,
+        rings: None,
+        glow: None,
     };
 
     let uranus = CelestialBody {
@@ -323,170 +836,381 @@ fn main() {
         orbit_speed: 0.1, // Orbital speed
         rotation_speed: 0.8, // Rotation speed on its axis
         color: Color::new(173, 216, 230, 255), // Light blue for Uranus
+        atmosphere: Some(Atmosphere::uranus()),
+        rings: Some(Rings::uranus()),
+        glow: None,
     };
 
-    let celestial_bodies = vec![sun, mercury.clone(), earth.clone(), mars.clone(), uranus.clone()];
+    let celestial_bodies = vec![sun, mercury, earth, mars, uranus];
+
+    // Mallas de los cascarones de atmósfera, una por cuerpo que la declare.
+    // Salen de una icoesfera: sin los triángulos finos de los polos, el borde que dejan las caras descartadas es parejo
+    let shell_base = icosphere(0.5, SHELL_SUBDIVISIONS);
+    let atmosphere_shells: Vec<Option<Vec<Vertex>>> = celestial_bodies
+        .iter()
+        .map(|body| body.atmosphere.as_ref().map(|atmosphere| atmosphere.shell_vertices(&shell_base)))
+        .collect();
+
+    // Esfera de radio 0.5 en varios niveles de detalle, generada en lugar de cargarse de un archivo.
+    // Todos los cuerpos la comparten; la escala de cada uno la pone su matriz de modelo
+    let sphere_lods = LodChain::sphere(0.5, &SPHERE_LODS);
+    // Nivel de detalle de cada cuerpo en cada vista, se cambia con histéresis
+    let mut active_lods = [vec![0; celestial_bodies.len()], vec![0; celestial_bodies.len()]];
+    let mut lod_colors = false;
+
+    // Mallas de los anillos, generadas según la configuración de cada cuerpo
+    let ring_meshes: Vec<Option<Vec<Vertex>>> = celestial_bodies
+        .iter()
+        .map(|body| body.rings.as_ref().map(|rings| rings.vertices()))
+        .collect();
 
     let mut time = 0.0;
+    let mut time_scale: f32 = 1.0;
+    let mut paused = false;
+    let mut render_mode = RenderMode::Solid;
+    let mut warp: Option<Warp> = None;
+    let mut focus = Focus::Body(EARTH);
+    // Objeto elegido con el ratón, resaltado con un contorno
+    let mut selection: Option<Focus> = None;
+
+    // La nave empieza en una órbita circular alrededor del sol, con la nariz en la dirección del movimiento
+    let orbit_speed = (GRAVITY * celestial_bodies[0].mass() / SHIP_START_ORBIT).sqrt();
+    let mut ship = Ship::new(
+        Vector3::new(SHIP_START_ORBIT, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, orbit_speed),
+        Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), -PI / 2.0),
+    );
+    let mut ship_response = Response::Bounce { restitution: 0.5 };
+    let mut show_prediction = true;
+
+    // Recorrido de cámara que se graba con K, se guarda con F5, se carga con F6 y se reproduce con F7
+    let mut recorded_path = CameraPath::default();
+    let mut playback: Option<Playback> = None;
+    let mut exported_frames = 0;
+    if let Some((path_file, output_dir)) = &render_job {
+        let path = CameraPath::load(path_file).expect("Failed to load camera path");
+        std::fs::create_dir_all(output_dir).expect("Failed to create output directory");
+        playback = Some(Playback::new(path));
+    }
 
     while !window.window_should_close() {
-        let dt = window.get_frame_time();
-        time += dt;
+        let dt = if render_job.is_some() { 1.0 / EXPORT_FPS } else { window.get_frame_time() };
+        // La simulación avanza según su propia velocidad; la cámara y la interfaz siguen el tiempo real
+        // Segundos simulados por segundo real, cero en pausa
+        let sim_speed = if paused { 0.0 } else { time_scale };
+        let sim_dt = dt * sim_speed;
+        time += sim_dt;
+
+        // Un recorrido en reproducción fija el tiempo de simulación y la cámara
+        let playback_frame = playback.as_mut().and_then(|active| active.advance(dt));
+        if let Some(keyframe) = playback_frame {
+            time = keyframe.time;
+        } else if playback.is_some() {
+            playback = None;
+            if render_job.is_some() {
+                break; // Terminó la exportación
+            }
+        }
+
+        if bindings.is_pressed(&window, Action::AddKeyframe) {
+            recorded_path.push(Keyframe { time, eye: camera.eye, target: camera.target, up: camera.up, fov: camera.fov });
+        }
+        if bindings.is_pressed(&window, Action::SavePath)
+            && let Err(error) = recorded_path.save(CAMERA_PATH_FILE)
+        {
+            eprintln!("Failed to save camera path: {error}");
+        }
+        if bindings.is_pressed(&window, Action::LoadPath) {
+            match CameraPath::load(CAMERA_PATH_FILE) {
+                Ok(path) => recorded_path = path,
+                Err(error) => eprintln!("Failed to load camera path: {error}"),
+            }
+        }
+        if bindings.is_pressed(&window, Action::PlayPath) {
+            playback = match playback {
+                Some(_) => None,
+                None if recorded_path.keyframes.is_empty() => None,
+                None => Some(Playback::new(recorded_path.clone())),
+            };
+        }
         
-        // Verificar teclas para teletransportación
-        if window.is_key_pressed(KeyboardKey::KEY_ONE) {
+        // Teclas de warp: eligen el destino y el viaje se anima en los siguientes cuadros
+        let warp_destination = if bindings.is_pressed(&window, Action::WarpHome) {
             // Vista 1: Estado inicial de la cámara
-            camera = Camera::new(
-                initial_camera_pos,
-                initial_camera_target,
-                initial_camera_up,
-            );
-        }
-        if window.is_key_pressed(KeyboardKey::KEY_TWO) {
+            Some(WarpDestination::View { eye: initial_camera_pos, target: initial_camera_target, up: initial_camera_up })
+        } else if bindings.is_pressed(&window, Action::WarpTop) {
             // Vista 2: Sistema solar desde arriba
-            camera = Camera::new(
-                Vector3::new(0.0, 100.0, 0.0), // eye
-                Vector3::new(0.0, 0.0, 0.0), // target
-                Vector3::new(0.0, 0.0, -1.0), // up
-            );
-        }
-        if window.is_key_pressed(KeyboardKey::KEY_THREE) {
-            // Vista 3: Cercana a la Tierra 
-            let camera_distance = earth.orbit_radius + 20.0; // Distancia desde el sol
-            let camera_x = (time * earth.orbit_speed).cos() * camera_distance;
-            let camera_z = (time * earth.orbit_speed).sin() * camera_distance;
-            
-            camera = Camera::new(
-                Vector3::new(camera_x, 20.0, camera_z), // eye
-                Vector3::new(0.0, -15.0, 0.0), // target
-                Vector3::new(0.0, 1.0, 0.0), // up
-            );
+            Some(WarpDestination::View {
+                eye: Vector3::new(0.0, 100.0, 0.0),
+                target: Vector3::new(0.0, 0.0, 0.0),
+                up: Vector3::new(0.0, 0.0, -1.0),
+            })
+        } else if bindings.is_pressed(&window, Action::WarpEarth) {
+            Some(WarpDestination::Body(EARTH)) // Vista 3: Tierra
+        } else if bindings.is_pressed(&window, Action::WarpMars) {
+            Some(WarpDestination::Body(MARS)) // Vista 4: Marte
+        } else if bindings.is_pressed(&window, Action::WarpUranus) {
+            Some(WarpDestination::Body(URANUS)) // Vista 5: Urano
+        } else if bindings.is_pressed(&window, Action::WarpSelected) {
+            // Viajar al objeto seleccionado
+            selection.map(|selected| match selected {
+                Focus::Body(index) => WarpDestination::Body(index),
+                Focus::Ship => WarpDestination::Ship,
+            })
+        } else if show_minimap && bindings.is_pressed(&window, Action::Select) && minimap.contains(window.get_mouse_position()) {
+            // Clic sobre el minimapa: viajar al cuerpo o a la nave bajo el cursor
+            minimap.pick(window.get_mouse_position(), &map_bodies(&celestial_bodies, time), &ship)
+        } else {
+            None
+        };
+
+        if let Some(destination) = warp_destination {
+            // Si ya había un viaje en curso, partir del campo de visión normal
+            if let Some(active) = &warp {
+                camera.fov = active.base_fov();
+            }
+            warp = Some(Warp::new(&camera, destination, WARP_DURATION));
+
+            // El destino pasa a ser el objeto seguido
+            match destination {
+                WarpDestination::Body(index) => focus = Focus::Body(index),
+                WarpDestination::Ship => focus = Focus::Ship,
+                WarpDestination::View { .. } => {}
+            }
         }
-        if window.is_key_pressed(KeyboardKey::KEY_FOUR) {
-            // Vista 4: Cercana a Marte 
-            let camera_distance = mars.orbit_radius + 20.0; // Distancia desde el sol
-            let camera_x = (time * mars.orbit_speed).cos() * camera_distance;
-            let camera_z = (time * mars.orbit_speed).sin() * camera_distance;
-            
-            camera = Camera::new(
-                Vector3::new(camera_x, 15.0, camera_z), // eye
-                Vector3::new(0.0, -10.0, 0.0), // target
-                Vector3::new(0.0, 1.0, 0.0), // up
-            );
+
+        // TAB recorre los objetos a seguir, T cambia entre libre, seguimiento y fijación
+        if bindings.is_pressed(&window, Action::CycleFocus) {
+            focus = focus.next(celestial_bodies.len());
+            selection = Some(focus);
+            camera.set_tracking(camera.tracking);
         }
-        if window.is_key_pressed(KeyboardKey::KEY_FIVE) {
-            // Vista 5: Cercana a Urano
-            let camera_distance = uranus.orbit_radius + 20.0; // Distancia desde el sol
-            let camera_x = (time * uranus.orbit_speed).cos() * camera_distance;
-            let camera_z = (time * uranus.orbit_speed).sin() * camera_distance;
-            
-            camera = Camera::new(
-                Vector3::new(camera_x, 10.0, camera_z), // eye
-                Vector3::new(0.0, -5.0, 0.0), // target
-                Vector3::new(0.0, 1.0, 0.0), // up
-            );
+        if bindings.is_pressed(&window, Action::CycleTracking) {
+            camera.set_tracking(match camera.tracking {
+                Tracking::Free => Tracking::Follow,
+                Tracking::Follow => Tracking::LockOn,
+                Tracking::LockOn => Tracking::Free,
+            });
         }
         
-        // Procesar entrada de cámara con movimiento 3D
-        camera.process_input(&window);
+        // Ayuda con los controles actuales
+        if bindings.is_pressed(&window, Action::ToggleHelp) {
+            show_help = !show_help;
+        }
+        if bindings.is_pressed(&window, Action::ToggleHud) {
+            show_hud = !show_hud;
+        }
+        if bindings.is_pressed(&window, Action::ToggleLabels) {
+            show_labels = !show_labels;
+        }
+        if bindings.is_pressed(&window, Action::ToggleMinimap) {
+            show_minimap = !show_minimap;
+        }
+        if bindings.is_pressed(&window, Action::SplitView) {
+            split_view = !split_view;
+        }
+
+        // Velocidad de la simulación: se divide o multiplica por dos, o se pausa
+        if bindings.is_pressed(&window, Action::TimeSlower) {
+            time_scale = (time_scale / 2.0).max(MIN_TIME_SCALE);
+        }
+        if bindings.is_pressed(&window, Action::TimeFaster) {
+            time_scale = (time_scale * 2.0).min(MAX_TIME_SCALE);
+        }
+        if bindings.is_pressed(&window, Action::Pause) {
+            paused = !paused;
+        }
+
+        // Colorear los cuerpos según su nivel de detalle
+        if bindings.is_pressed(&window, Action::LodColors) {
+            lod_colors = !lod_colors;
+        }
+
+        // Alternar el modo alambre para depurar mallas y recorte
+        if bindings.is_pressed(&window, Action::ToggleWireframe) {
+            render_mode = match render_mode {
+                RenderMode::Solid => RenderMode::Wireframe,
+                RenderMode::Wireframe => RenderMode::Solid,
+            };
+        }
+
+        // Recorrer los modos de cámara: orbital, vuelo libre, persecución y cabina
+        if bindings.is_pressed(&window, Action::CycleCamera) {
+            camera.cycle_mode();
+        }
+
+        // Física de la nave en este cuadro, la usan las cámaras que la siguen y el render
+        // Con las cámaras de persecución y cabina los controles pilotan la nave
+        let ship_input = if warp.is_none() && playback_frame.is_none() && matches!(camera.mode, CameraMode::Chase | CameraMode::Cockpit) {
+            ShipInput::from_bindings(&window, &bindings)
+        } else {
+            ShipInput::default()
+        };
+        if bindings.is_pressed(&window, Action::FlightAssist) {
+            ship.flight_assist = !ship.flight_assist;
+        }
+        // B cambia la respuesta de la nave al chocar: detenerse, rebotar o deslizarse
+        if bindings.is_pressed(&window, Action::CollisionResponse) {
+            ship_response = match ship_response {
+                Response::Stop => Response::Bounce { restitution: 0.5 },
+                Response::Bounce { .. } => Response::Slide { friction: 0.1 },
+                Response::Slide { .. } => Response::Stop,
+            };
+        }
+        if bindings.is_pressed(&window, Action::TogglePrediction) {
+            show_prediction = !show_prediction;
+        }
+        // Los planetas se mueven durante los subpasos de la nave, que empiezan donde terminó el cuadro anterior
+        let frame_start = time - sim_dt;
+        ship.update(ship_input, |t| attractors(&celestial_bodies, frame_start + t), sim_dt);
+        collide_ship(&mut ship, &ship_collider, ship_response, &celestial_bodies, time);
+        let nave_model_matrix = ship.model_matrix();
+
+        // Vistas en pantalla y la que está bajo el cursor, para repartir la entrada del ratón.
+        // El minimapa queda encima de la vista principal y se queda con el ratón que tiene sobre él
+        let cursor = window.get_mouse_position();
+        let over_minimap = show_minimap && minimap.contains(cursor);
+        let views = View::layout(screen, split_view);
+        let hovered = views.iter().find(|(viewport, _)| !over_minimap && viewport.contains(cursor));
+
+        if let Some(keyframe) = playback_frame {
+            camera.look_at(keyframe.eye, keyframe.target, keyframe.up);
+            camera.fov = keyframe.fov;
+        } else if let Some(active) = &mut warp {
+            // Durante el viaje la cámara sigue la trayectoria, recalculando el destino si el cuerpo se movió
+            active.update(dt);
+            let (end_eye, end_target, end_up) = match active.destination {
+                WarpDestination::View { eye, target, up } => (eye, target, up),
+                WarpDestination::Body(index) => body_framing(&celestial_bodies[index], time, active.base_fov()),
+                WarpDestination::Ship => ship_framing(&ship, camera.chase_offset),
+            };
+            let (eye, target, up) = active.pose(end_eye, end_target, end_up);
+            camera.look_at(eye, target, up);
+            camera.fov = active.fov();
+
+            if active.finished() {
+                camera.fov = active.base_fov();
+                warp = None;
+            }
+        } else {
+            // Procesar entrada de cámara con movimiento 3D
+            camera.process_input(&window, &bindings, dt);
+            // El ratón solo orbita, desplaza o acerca la cámara principal cuando está sobre su vista
+            if let Some(&(viewport, View::Main)) = hovered {
+                camera.process_mouse(&window, &bindings, viewport);
+            }
+
+            // Mantener la cámara con el objeto seleccionado según el modo de seguimiento
+            let focus_position = match focus {
+                Focus::Body(index) => celestial_bodies[index].position(time),
+                Focus::Ship => ship.position,
+            };
+            camera.track(focus_position);
+
+            // Cámaras de persecución y de cabina
+            camera.attach_to_ship(nave_model_matrix, dt);
+        }
         
         // Verificar colisiones y ajustar la posición de la cámara si es necesario
-        let (adjusted_eye, adjusted_target) = avoid_collision(camera.eye, camera.target, &celestial_bodies, time);
-        camera.eye = adjusted_eye;
-        camera.target = adjusted_target;
+        collide_camera(&mut camera, &celestial_bodies, time, sim_speed);
+
+        // Selección con el ratón: el objeto bajo el cursor pasa a ser el seguido; un clic en el vacío la quita.
+        // Los clics sobre el minimapa ya se usaron para elegir el destino del warp
+        if bindings.is_pressed(&window, Action::Select)
+            && let Some((viewport, view)) = hovered
+        {
+            // El rayo sale de la cámara de la vista bajo el cursor
+            let view_camera = view.camera(&camera, &overview_camera);
+            let ray = Ray::from_screen(
+                viewport.to_local(cursor),
+                viewport.width as f32,
+                viewport.height as f32,
+                view_camera.get_view_matrix(),
+                create_projection_matrix(view_camera.fov, viewport.aspect(), 0.1, 100.0),
+            );
+            selection = pick(ray, &celestial_bodies, time, &ship_mesh, nave_model_matrix);
+            if let Some(selected) = selection {
+                focus = selected;
+                camera.set_tracking(camera.tracking);
+            }
+        }
         
         framebuffer.clear();
         framebuffer.set_current_color(Color::new(200, 200, 255, 255));
 
-        // Render each celestial body FIRST
-        for mut body in celestial_bodies.clone() {
-            // Update orbital position for planets (not for the sun)
-            if body.name != "Sun" {
-                body.translation.x = (time * body.orbit_speed).cos() * body.orbit_radius;
-                body.translation.z = (time * body.orbit_speed).sin() * body.orbit_radius;
-            }
-            
-            // Update rotation for all bodies
-            body.rotation.y += dt * body.rotation_speed;
-            
-            // Set color for the body
-            framebuffer.set_current_color(body.color);
-            
-            // Crear matrices de transformación para este cuerpo celeste
-            let model_matrix = create_model_matrix(
-                body.translation, 
-                body.scale, 
-                body.rotation
-            );
-            let view_matrix = camera.get_view_matrix();
-            let projection_matrix = create_projection_matrix(PI / 3.0, window_width as f32 / window_height as f32, 0.1, 100.0);
-            let viewport_matrix = create_viewport_matrix(0.0, 0.0, window_width as f32, window_height as f32);
-
-            // Crear uniforms
-            let uniforms = Uniforms {
-                model_matrix,
-                view_matrix,
-                projection_matrix,
-                viewport_matrix,
-                time,
-                dt,
-            };
+        // Trayectoria prevista de la nave, se recalcula cada cuadro para reflejar el empuje
+        let prediction = show_prediction.then(|| predict(&ship, |t| attractors(&celestial_bodies, time + t), PREDICTION_SECONDS, PREDICTION_STEP));
+        let scene = Scene {
+            celestial_bodies: &celestial_bodies,
+            sphere_lods: &sphere_lods,
+            lod_colors,
+            atmosphere_shells: &atmosphere_shells,
+            ring_meshes: &ring_meshes,
+            ship_vertices: &nave_vertex_array,
+            ship_bounds: nave_obj.bounds,
+            ship_matrix: nave_model_matrix,
+            prediction: prediction.as_ref(),
+            light: &light,
+            selection,
+            mode: render_mode,
+            time,
+            dt,
+        };
 
-            render(&mut framebuffer, &uniforms, &vertex_array, &light, &body.name);
+        // Cada vista dibuja la escena con su cámara en su rectángulo, con su propia profundidad
+        let mut world_uniforms = Vec::with_capacity(views.len());
+        let mut cull_stats = CullStats::default();
+        for (viewport, view) in &views {
+            let lods = &mut active_lods[*view as usize];
+            world_uniforms.push(render_view(&mut framebuffer, &scene, view.camera(&camera, &overview_camera), viewport, lods, &mut cull_stats));
         }
+        framebuffer.set_scissor(None);
 
-        // Crear matrices de transformación comunes
-        let view_matrix = camera.get_view_matrix();
-        let projection_matrix = create_projection_matrix(PI / 3.0, window_width as f32 / window_height as f32, 0.1, 100.0);
-        let viewport_matrix = create_viewport_matrix(0.0, 0.0, window_width as f32, window_height as f32);
+        // Contorno del objeto seleccionado, con un pulso suave
+        framebuffer.draw_selection_outline(SELECTION_COLOR, 0.65 + 0.25 * (time * 4.0).sin(), SELECTION_THICKNESS);
 
-        // Dibujar las órbitas de los planetas en blanco AFTER rendering the planets
-        for body in &celestial_bodies {
-            if body.name != "Sun" {
-                let orbit_color = Color::new(255, 255, 255, 50); // Blanco con menor transparencia (más discreto)
-                draw_orbit_3d(&mut framebuffer, body.orbit_radius, orbit_color, &view_matrix, &projection_matrix, &viewport_matrix);
-            }
+        // Estelas del warp encima de todo
+        if let Some(active) = &warp {
+            active.draw_streaks(&mut framebuffer);
         }
 
-        // Renderizar la nave espacial en su órbita angulada
-        {
-            // Calcular posición de la nave en su órbita
-            let nave_orbit_radius = 30.0; // Radio de la órbita de la nave
-            let nave_orbit_speed = 0.4; // Velocidad de la nave
-            let nave_angle = time * nave_orbit_speed;
-            
-            // Posición de la nave en su órbita angulada
-            let nave_x = nave_angle.cos() * nave_orbit_radius;
-            let nave_y = (nave_angle * 0.5).sin() * 10.0; // Movimiento vertical para crear órbita angulada
-            let nave_z = nave_angle.sin() * nave_orbit_radius;
-            
-            // Calcular rotación de la nave para que apunte en la dirección de movimiento
-            let rotation_y = nave_angle + PI / 2.0; // Ajustar para que apunte en la dirección correcta
-            let rotation_x = (nave_angle * 0.5).cos() * 0.2; // Pequeña rotación en X para seguir la órbita
-            
-            // Crear matriz de modelo para la nave
-            let nave_model_matrix = create_model_matrix(
-                Vector3::new(nave_x, nave_y, nave_z),
-                0.3, // Escala de la nave
-                Vector3::new(rotation_x, rotation_y, 0.0) // Rotación de la nave
-            );
-            
-            // Crear uniforms para la nave
-            let nave_uniforms = Uniforms {
-                model_matrix: nave_model_matrix,
-                view_matrix: camera.get_view_matrix(),
-                projection_matrix: create_projection_matrix(PI / 3.0, window_width as f32 / window_height as f32, 0.1, 100.0),
-                viewport_matrix: create_viewport_matrix(0.0, 0.0, window_width as f32, window_height as f32),
-                time,
-                dt,
-            };
-            
-            // Renderizar la nave con su shader específico
-            render(&mut framebuffer, &nave_uniforms, &nave_vertex_array, &light, "Nave");
+        if let Some((_, output_dir)) = &render_job {
+            framebuffer.color_buffer.export_image(&format!("{output_dir}/frame_{exported_frames:05}.png"));
+            exported_frames += 1;
+        }
+
+        // Las etiquetas, el minimapa, el HUD y la ayuda van encima de todo y no salen en los cuadros exportados
+        if show_labels {
+            let mut targets: Vec<LabelTarget> = celestial_bodies
+                .iter()
+                .map(|body| LabelTarget { name: &body.name, position: body.position(time), radius: body.radius() })
+                .collect();
+            targets.push(LabelTarget { name: "Ship", position: ship.position, radius: ship_mesh.bounds_radius * ship.scale });
+            // Solo en la vista de la cámara principal, la primera
+            let (main_viewport, _) = views[0];
+            main_viewport.scissor(&mut framebuffer);
+            draw_labels(&mut framebuffer, &targets, &world_uniforms[0], &main_viewport);
+            framebuffer.set_scissor(None);
+        }
+        if show_minimap {
+            minimap.draw(&mut framebuffer, &map_bodies(&celestial_bodies, time), &ship, &camera, views[0].0.aspect());
+        }
+        if show_hud {
+            draw_hud(&mut framebuffer, &HudInfo {
+                fps: window.get_fps(),
+                frame_time: dt,
+                sim_time: time,
+                time_scale: sim_speed,
+                camera: &camera,
+                following: focus.name(&celestial_bodies),
+                selected: selection.map(|selected| selected.name(&celestial_bodies)),
+                draws: cull_stats,
+            });
+        }
+        if show_help {
+            bindings.draw_help(&mut framebuffer, Context::for_camera(camera.mode));
         }
 
         framebuffer.swap_buffers(&mut window, &raylib_thread);
-        
-        thread::sleep(Duration::from_millis(16));
     }
 }
\ No newline at end of file
@@ -97,7 +97,23 @@ pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3)
         0.0, 0.0, 0.0, 1.0
     );
 
-    scale_matrix * translation_matrix * rotation_matrix 
+    // Raylib's `*` applies the left matrix first: scale, then rotate in place, then translate
+    scale_matrix * rotation_matrix * translation_matrix
+}
+
+/// Creates a model matrix from translation, uniform scale and an orientation quaternion
+pub fn create_model_matrix_from_quaternion(translation: Vector3, scale: f32, rotation: Quaternion) -> Matrix {
+    // The columns are the model axes after rotating and scaling them
+    let x_axis = Vector3::new(1.0, 0.0, 0.0).rotate_by(rotation) * scale;
+    let y_axis = Vector3::new(0.0, 1.0, 0.0).rotate_by(rotation) * scale;
+    let z_axis = Vector3::new(0.0, 0.0, 1.0).rotate_by(rotation) * scale;
+
+    new_matrix4(
+        x_axis.x, y_axis.x, z_axis.x, translation.x,
+        x_axis.y, y_axis.y, z_axis.y, translation.y,
+        x_axis.z, y_axis.z, z_axis.z, translation.z,
+        0.0,      0.0,      0.0,      1.0
+    )
 }
 
 /// Creates a view matrix using camera position, target, and up vector
@@ -160,6 +176,18 @@ pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) ->
     )
 }
 
+/// Creates an orthographic projection matrix
+/// left, right, bottom, top: Extent of the view volume in view space
+/// near, far: Distances to the clipping planes along -Z
+pub fn create_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left),
+        0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom),
+        0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
 /// Creates a viewport matrix to transform NDC coordinates to screen space
 /// x, y: Viewport position (typically 0, 0)
 /// width, height: Viewport dimensions in pixels
@@ -0,0 +1,212 @@
+// minimap.rs
+use raylib::prelude::*;
+use crate::Uniforms;
+use crate::camera::Camera;
+use crate::framebuffer::{BlendMode, Framebuffer};
+use crate::line3d::{LineStyle, draw_line_3d, draw_polyline_3d, project_point};
+use crate::matrix::{create_orthographic_matrix, create_view_matrix};
+use crate::ship::Ship;
+use crate::viewport::Viewport;
+use crate::warp::WarpDestination;
+
+// Height of the overhead camera and depth of its view volume, enough to hold the whole system
+const CAMERA_HEIGHT: f32 = 100.0;
+const DEPTH_RANGE: f32 = 200.0;
+// Bodies smaller than this many pixels are drawn at this size so they stay visible and clickable
+const MIN_MARKER_RADIUS: f32 = 2.5;
+// Extra pixels around a marker that still count as clicking it
+const PICK_TOLERANCE: f32 = 4.0;
+// Size of the ship arrow in pixels
+const SHIP_ARROW: f32 = 7.0;
+// How far the main camera's frustum is drawn, in world units
+const FRUSTUM_LENGTH: f32 = 40.0;
+
+/// What the minimap needs to know about a celestial body
+pub struct MapBody {
+    pub position: Vector3,
+    pub radius: f32,
+    pub orbit_radius: f32, // Zero for bodies that don't orbit
+    pub color: Vector3,
+}
+
+/// Top-down view of the system drawn by an orthographic camera into a square of the framebuffer
+pub struct Minimap {
+    pub viewport: Viewport,
+    pub extent: f32, // World units from the center of the map to its edges
+}
+
+impl Minimap {
+    /// Square in the bottom-right corner of a `width` × `height` screen
+    pub fn bottom_right(width: i32, height: i32, size: i32, margin: i32, extent: f32) -> Self {
+        Minimap { viewport: Viewport::new(width - size - margin, height - size - margin, size, size), extent }
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        self.viewport.contains(point)
+    }
+
+    /// Camera above the sun looking down, with -Z at the top like the overhead warp view.
+    /// The viewport maps its view volume onto the minimap square only.
+    fn uniforms(&self) -> Uniforms {
+        Uniforms {
+            model_matrix: Matrix::identity(),
+            view_matrix: create_view_matrix(
+                Vector3::new(0.0, CAMERA_HEIGHT, 0.0),
+                Vector3::zero(),
+                Vector3::new(0.0, 0.0, -1.0),
+            ),
+            projection_matrix: create_orthographic_matrix(-self.extent, self.extent, -self.extent, self.extent, 0.0, DEPTH_RANGE),
+            viewport_matrix: self.viewport.matrix(),
+            camera_position: Vector3::new(0.0, CAMERA_HEIGHT, 0.0),
+            time: 0.0,
+            dt: 0.0,
+        }
+    }
+
+    fn pixels_per_unit(&self) -> f32 {
+        self.viewport.width as f32 / (2.0 * self.extent)
+    }
+
+    fn marker_radius(&self, body: &MapBody) -> f32 {
+        (body.radius * self.pixels_per_unit()).max(MIN_MARKER_RADIUS)
+    }
+
+    /// Draws orbits, bodies, the ship and the part of the system the main camera sees.
+    /// The square gets its own cleared depth, so the scene behind it never hides the map.
+    pub fn draw(&self, framebuffer: &mut Framebuffer, bodies: &[MapBody], ship: &Ship, camera: &Camera, aspect: f32) {
+        let uniforms = self.uniforms();
+        let Viewport { x, y, width, height } = self.viewport;
+        self.viewport.begin(framebuffer);
+        framebuffer.shade_rect(x, y, width, height, Vector3::zero(), 0.4);
+
+        let orbit_style = LineStyle::solid(Vector3::new(1.0, 1.0, 1.0), 0.25, 1.0);
+        for body in bodies.iter().filter(|body| body.orbit_radius > 0.0) {
+            let points: Vec<Vector3> = (0..64)
+                .map(|i| {
+                    let angle = i as f32 / 64.0 * 2.0 * std::f32::consts::PI;
+                    Vector3::new(angle.cos() * body.orbit_radius, 0.0, angle.sin() * body.orbit_radius)
+                })
+                .collect();
+            draw_polyline_3d(framebuffer, &points, true, &uniforms, &orbit_style);
+        }
+
+        self.draw_frustum(framebuffer, camera, aspect, &uniforms);
+
+        for body in bodies {
+            if let Some(center) = project_point(body.position, &uniforms) {
+                self.fill_disc(framebuffer, center, self.marker_radius(body), body.color);
+            }
+        }
+
+        self.draw_ship(framebuffer, ship, &uniforms);
+        self.draw_border(framebuffer);
+        framebuffer.set_scissor(None);
+    }
+
+    /// Warp destination under `cursor`: the ship if it is there, otherwise the nearest body marker
+    pub fn pick(&self, cursor: Vector2, bodies: &[MapBody], ship: &Ship) -> Option<WarpDestination> {
+        let uniforms = self.uniforms();
+        let distance_to = |position: Vector3| {
+            project_point(position, &uniforms).map(|screen| Vector2::new(screen.x, screen.y).distance_to(cursor))
+        };
+
+        if distance_to(ship.position).is_some_and(|distance| distance <= SHIP_ARROW + PICK_TOLERANCE) {
+            return Some(WarpDestination::Ship);
+        }
+        bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, body)| {
+                let distance = distance_to(body.position)?;
+                (distance <= self.marker_radius(body) + PICK_TOLERANCE).then_some((index, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| WarpDestination::Body(index))
+    }
+
+    /// Wedge from the camera to its view at `FRUSTUM_LENGTH`, seen from above
+    fn draw_frustum(&self, framebuffer: &mut Framebuffer, camera: &Camera, aspect: f32, uniforms: &Uniforms) {
+        // Rows of the view matrix: the camera's right, up and backward vectors in world space
+        let view = camera.get_view_matrix();
+        let right = Vector3::new(view.m0, view.m4, view.m8);
+        let up = Vector3::new(view.m1, view.m5, view.m9);
+        let forward = -Vector3::new(view.m2, view.m6, view.m10);
+
+        let half_height = (camera.fov / 2.0).tan() * FRUSTUM_LENGTH;
+        let half_width = half_height * aspect;
+        let center = camera.eye + forward * FRUSTUM_LENGTH;
+        let corners = [
+            center - right * half_width - up * half_height,
+            center + right * half_width - up * half_height,
+            center + right * half_width + up * half_height,
+            center - right * half_width + up * half_height,
+        ];
+
+        let style = LineStyle::solid(Vector3::new(1.0, 0.9, 0.4), 0.6, 1.0);
+        for corner in corners {
+            draw_line_3d(framebuffer, camera.eye, corner, uniforms, &style);
+        }
+        draw_polyline_3d(framebuffer, &corners, true, uniforms, &style);
+    }
+
+    /// Arrow pointing where the ship's nose points, projected onto the map
+    fn draw_ship(&self, framebuffer: &mut Framebuffer, ship: &Ship, uniforms: &Uniforms) {
+        let Some(center) = project_point(ship.position, uniforms) else {
+            return;
+        };
+        let Some(ahead) = project_point(ship.position + Vector3::new(1.0, 0.0, 0.0).rotate_by(ship.orientation), uniforms) else {
+            return;
+        };
+
+        // With the nose straight up or down there is no heading on the map, the arrow points up
+        let heading = Vector2::new(ahead.x - center.x, ahead.y - center.y);
+        let heading = if heading.length() > 1e-3 { heading.normalized() } else { Vector2::new(0.0, -1.0) };
+        let side = Vector2::new(-heading.y, heading.x);
+
+        let at = |offset: Vector2| Vector3::new(center.x + offset.x, center.y + offset.y, center.z);
+        let tip = at(heading * SHIP_ARROW);
+        let left = at((-heading + side * 0.7) * (SHIP_ARROW * 0.7));
+        let right = at((-heading - side * 0.7) * (SHIP_ARROW * 0.7));
+
+        let color = Vector3::new(0.3, 1.0, 0.5);
+        for (start, end) in [(tip, left), (left, right), (right, tip)] {
+            framebuffer.draw_line_aa(start, end, color, 1.0, 1.5);
+        }
+    }
+
+    /// Solid circle around a projected center, the scissor cuts it at the edges of the map
+    fn fill_disc(&self, framebuffer: &mut Framebuffer, center: Vector3, radius: f32, color: Vector3) {
+        let min_x = (center.x - radius) as i32;
+        let max_x = (center.x + radius) as i32;
+        let min_y = (center.y - radius) as i32;
+        let max_y = (center.y + radius) as i32;
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let dx = x as f32 + 0.5 - center.x;
+                let dy = y as f32 + 0.5 - center.y;
+                // Half a pixel of soft edge
+                let coverage = (radius + 0.5 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    framebuffer.blend_point(x, y, color, coverage, center.z, BlendMode::Alpha);
+                }
+            }
+        }
+    }
+
+    fn draw_border(&self, framebuffer: &mut Framebuffer) {
+        let color = Vector3::new(0.7, 0.8, 0.9);
+        // Through the middle of the outermost pixels, so the border stays inside the square
+        let (left, top) = (self.viewport.x as f32 + 0.5, self.viewport.y as f32 + 0.5);
+        let (right, bottom) = (left + (self.viewport.width - 1) as f32, top + (self.viewport.height - 1) as f32);
+        let corners = [
+            Vector3::new(left, top, -1.0),
+            Vector3::new(right, top, -1.0),
+            Vector3::new(right, bottom, -1.0),
+            Vector3::new(left, bottom, -1.0),
+        ];
+        for i in 0..4 {
+            framebuffer.draw_line_aa(corners[i], corners[(i + 1) % 4], color, 0.8, 1.0);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 // obj.rs
+use crate::culling::Bounds;
 use crate::vertex::Vertex;
 use raylib::math::{Vector2, Vector3};
 use tobj;
@@ -6,6 +7,7 @@ use tobj;
 pub struct Obj {
     pub vertices: Vec<Vertex>,
     pub indices: Vec<u32>,
+    pub bounds: Bounds, // Volumes around every vertex, for frustum culling
 }
 
 impl Obj {
@@ -47,7 +49,8 @@ impl Obj {
             indices.extend_from_slice(&mesh.indices);
         }
 
-        Ok(Obj { vertices, indices })
+        let bounds = Bounds::from_vertices(&vertices);
+        Ok(Obj { vertices, indices, bounds })
     }
 
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
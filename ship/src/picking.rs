@@ -0,0 +1,88 @@
+// picking.rs
+use raylib::prelude::*;
+use crate::collision::MeshCollider;
+use crate::matrix::multiply_matrix_vector4;
+
+/// Half-line in world space, `direction` has unit length
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Vector3,
+    pub direction: Vector3,
+}
+
+impl Ray {
+    /// Ray through the pixel `cursor` of a `width` × `height` screen, found by unprojecting
+    /// the point on the near and far planes with the inverse of the view and projection matrices
+    pub fn from_screen(cursor: Vector2, width: f32, height: f32, view_matrix: Matrix, projection_matrix: Matrix) -> Ray {
+        // Inverse of the viewport transform
+        let ndc_x = 2.0 * cursor.x / width - 1.0;
+        let ndc_y = 1.0 - 2.0 * cursor.y / height;
+
+        // `view * projection` applies the view first, like the vertex shader
+        let inverse = (view_matrix * projection_matrix).inverted();
+        let unproject = |ndc_z: f32| {
+            let point = multiply_matrix_vector4(&inverse, &Vector4::new(ndc_x, ndc_y, ndc_z, 1.0));
+            Vector3::new(point.x, point.y, point.z) / point.w
+        };
+
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        Ray { origin: near, direction: (far - near).normalized() }
+    }
+}
+
+/// Distance along the ray to the first point of the sphere, zero when the ray starts inside it
+pub fn ray_sphere(ray: Ray, center: Vector3, radius: f32) -> Option<f32> {
+    let offset = ray.origin - center;
+    let b = offset.dot(ray.direction);
+    let c = offset.dot(offset) - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    let discriminant = b * b - c;
+    if b > 0.0 || discriminant < 0.0 {
+        return None; // Pointing away, or passing beside it
+    }
+    Some(-b - discriminant.sqrt())
+}
+
+/// Möller–Trumbore intersection, both faces count
+pub fn ray_triangle(ray: Ray, a: Vector3, b: Vector3, c: Vector3) -> Option<f32> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < 1e-8 {
+        return None; // Parallel to the triangle
+    }
+
+    let inverse = 1.0 / determinant;
+    let s = ray.origin - a;
+    let u = s.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inverse;
+    (t >= 0.0).then_some(t)
+}
+
+/// Nearest hit against a mesh placed in the world by `transform`.
+/// The bounding sphere is tested first, so rays far from the mesh skip the triangles.
+pub fn ray_mesh(ray: Ray, mesh: &MeshCollider, transform: Matrix) -> Option<f32> {
+    let scale = (Vector3::new(1.0, 0.0, 0.0).transform_with(transform) - Vector3::zero().transform_with(transform)).length();
+    ray_sphere(ray, mesh.bounds_center.transform_with(transform), mesh.bounds_radius * scale)?;
+
+    mesh.triangles
+        .iter()
+        .filter_map(|[a, b, c]| {
+            ray_triangle(ray, a.transform_with(transform), b.transform_with(transform), c.transform_with(transform))
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
@@ -0,0 +1,111 @@
+// ring.rs
+use raylib::prelude::*;
+use crate::vertex::Vertex;
+
+// Radial subdivisions of the annulus, keeps the affine interpolation of long triangles from warping the bands
+const RADIAL_STEPS: usize = 4;
+
+/// A region of the ring with its own density, in normalized radius (0 = inner edge, 1 = outer edge)
+#[derive(Clone)]
+pub struct RingBand {
+    pub start: f32,
+    pub end: f32,
+    pub density: f32,
+}
+
+/// Ring system around a body. Radii are in the body's model space, where the planet has radius `planet_radius`.
+#[derive(Clone)]
+pub struct Rings {
+    pub planet_radius: f32,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub segments: usize,           // Segments around the annulus
+    pub tilt: Vector3,             // Rotation of the ring plane relative to the orbital plane
+    pub color: Vector3,
+    pub opacity: f32,              // Alpha of a band with density 1
+    pub bands: Vec<RingBand>,
+    pub ripple_frequency: f32,     // Fine ringlets layered over the bands
+    pub back_scattering: f32,      // How much light passes through when lit from behind
+}
+
+impl Rings {
+    /// Narrow, dark rings of Uranus, almost perpendicular to the orbit because of its axial tilt
+    pub fn uranus() -> Self {
+        Rings {
+            planet_radius: 0.5,
+            inner_radius: 0.8,
+            outer_radius: 1.3,
+            segments: 96,
+            tilt: Vector3::new(0.0, 0.0, 1.71),
+            color: Vector3::new(0.55, 0.6, 0.65),
+            opacity: 0.85,
+            bands: vec![
+                RingBand { start: 0.05, end: 0.1, density: 0.5 },
+                RingBand { start: 0.22, end: 0.26, density: 0.6 },
+                RingBand { start: 0.4, end: 0.44, density: 0.55 },
+                RingBand { start: 0.58, end: 0.62, density: 0.7 },
+                RingBand { start: 0.86, end: 0.96, density: 1.0 }, // Epsilon ring
+            ],
+            ripple_frequency: 40.0,
+            back_scattering: 0.8,
+        }
+    }
+
+    /// Density of the ring material at normalized radius `t`
+    pub fn density(&self, t: f32) -> f32 {
+        if !(0.0..=1.0).contains(&t) {
+            return 0.0;
+        }
+
+        let mut density: f32 = 0.0;
+        for band in &self.bands {
+            if t >= band.start && t <= band.end {
+                // Soften the band edges
+                let width = band.end - band.start;
+                let edge = ((t - band.start).min(band.end - t) / (width * 0.25)).min(1.0);
+                density = density.max(band.density * edge);
+            }
+        }
+
+        let ripple = (t * self.ripple_frequency).sin() * 0.15 + 0.85;
+        density * ripple
+    }
+
+    pub fn vertices(&self) -> Vec<Vertex> {
+        ring_mesh(self.inner_radius, self.outer_radius, self.segments)
+    }
+}
+
+/// Generates a flat annulus on the XZ plane as a triangle list.
+/// u runs from the inner (0) to the outer (1) edge and v goes around the ring.
+pub fn ring_mesh(inner_radius: f32, outer_radius: f32, segments: usize) -> Vec<Vertex> {
+    let segments = segments.max(3);
+    let normal = Vector3::new(0.0, 1.0, 0.0);
+    let mut vertices = Vec::with_capacity(segments * RADIAL_STEPS * 6);
+
+    let point = |step: usize, segment: usize| {
+        let u = step as f32 / RADIAL_STEPS as f32;
+        let v = segment as f32 / segments as f32;
+        let radius = inner_radius + (outer_radius - inner_radius) * u;
+        let angle = v * 2.0 * std::f32::consts::PI;
+        Vertex::new(
+            Vector3::new(angle.cos() * radius, 0.0, angle.sin() * radius),
+            normal,
+            Vector2::new(u, v),
+        )
+    };
+
+    for segment in 0..segments {
+        for step in 0..RADIAL_STEPS {
+            let a = point(step, segment);
+            let b = point(step + 1, segment);
+            let c = point(step + 1, segment + 1);
+            let d = point(step, segment + 1);
+
+            vertices.extend([a.clone(), b, c.clone()]);
+            vertices.extend([a, c, d]);
+        }
+    }
+
+    vertices
+}
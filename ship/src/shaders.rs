@@ -3,6 +3,8 @@ use crate::vertex::Vertex;
 use crate::Uniforms;
 use crate::matrix::multiply_matrix_vector4;
 use crate::fragment::Fragment;
+use crate::atmosphere::{Atmosphere, ray_sphere_intersect};
+use crate::ring::Rings;
 
 fn transform_normal(normal: &Vector3, model_matrix: &Matrix) -> Vector3 {
     // Convierte el normal a coordenadas homogéneas (añade coordenada w = 0.0)
@@ -65,6 +67,7 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         position: vertex.position,
         normal: vertex.normal,
         tex_coords: vertex.tex_coords,
+        tangent: vertex.tangent,
         color: vertex.color,
         transformed_position,
         transformed_normal: transform_normal(&vertex.normal, &uniforms.model_matrix),
@@ -328,4 +331,58 @@ pub fn nave_fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3
         pulsed_color.y.clamp(0.0, 1.0),
         pulsed_color.z.clamp(0.0, 1.0),
     )
+}
+
+// Shader de atmósfera: dispersión simple Rayleigh/Mie muestreada a lo largo del rayo de vista
+// `eye` y `light_position` van en el espacio del modelo, donde está definida la atmósfera
+pub fn atmosphere_fragment_shader(fragment: &Fragment, atmosphere: &Atmosphere, eye: Vector3, light_position: Vector3) -> Vector3 {
+    atmosphere.scatter(eye, fragment.world_position, light_position)
+}
+
+// Shader de anillos: bandas de densidad, transparencia, contraluz y sombra del planeta.
+// `eye` y `light_position` van en el espacio del modelo, donde el anillo está en el plano XZ.
+// Devuelve el color en xyz y la opacidad en w.
+pub fn ring_fragment_shader(fragment: &Fragment, rings: &Rings, eye: Vector3, light_position: Vector3) -> Vector4 {
+    let pos = fragment.world_position;
+
+    // Radio normalizado dentro del anillo (0 = borde interior, 1 = borde exterior)
+    let radius = (pos.x * pos.x + pos.z * pos.z).sqrt();
+    let t = (radius - rings.inner_radius) / (rings.outer_radius - rings.inner_radius);
+    let alpha = (rings.density(t) * rings.opacity).clamp(0.0, 1.0);
+    if alpha <= 0.0 {
+        return Vector4::new(0.0, 0.0, 0.0, 0.0);
+    }
+
+    let normal = Vector3::new(0.0, 1.0, 0.0);
+    let light_dir = (light_position - pos).normalized();
+    let view_dir = (eye - pos).normalized();
+
+    // Sombra del planeta sobre el anillo
+    let in_shadow = matches!(
+        ray_sphere_intersect(pos, light_dir, rings.planet_radius),
+        Some((t_near, _)) if t_near > 0.0
+    );
+
+    let light_side = normal.dot(light_dir);
+    let view_side = normal.dot(view_dir);
+    let lighting = if in_shadow {
+        0.0
+    } else if light_side * view_side >= 0.0 {
+        // Iluminado desde el mismo lado que se mira
+        light_side.abs()
+    } else {
+        // Contraluz: la luz atraviesa las zonas menos densas y se dispersa hacia adelante
+        let forward = view_dir.dot(-light_dir).max(0.0);
+        rings.back_scattering * (1.0 - alpha) * (0.3 + 0.7 * forward.powf(4.0))
+    };
+
+    let ambient = 0.08;
+    let color = rings.color * (ambient + lighting);
+
+    Vector4::new(
+        color.x.clamp(0.0, 1.0),
+        color.y.clamp(0.0, 1.0),
+        color.z.clamp(0.0, 1.0),
+        alpha,
+    )
 }
\ No newline at end of file
@@ -0,0 +1,162 @@
+// ship.rs
+use raylib::prelude::*;
+use crate::matrix::create_model_matrix_from_quaternion;
+use crate::input::{Action, Bindings};
+
+// Gravitational constant of the scene, bodies have a mass of scale³
+pub const GRAVITY: f32 = 0.08;
+// Longest step integrated at once, keeps the simulation stable when a frame takes long
+const MAX_STEP: f32 = 1.0 / 30.0;
+
+/// Body that pulls the ship towards it
+pub struct Attractor {
+    pub position: Vector3,
+    pub mass: f32,
+    pub radius: f32, // Inside this radius the pull stops growing
+}
+
+/// Pilot commands in ship space, each component between -1 and 1.
+/// The ship's nose points along +X, its canopy along +Y and its right wing along +Z.
+#[derive(Clone, Copy, Default)]
+pub struct ShipInput {
+    pub thrust: Vector3, // x: forward, y: up, z: right
+    pub torque: Vector3, // x: roll right, y: yaw left, z: pitch up
+}
+
+impl ShipInput {
+    /// Reads the ship actions of the current bindings
+    pub fn from_bindings(window: &RaylibHandle, bindings: &Bindings) -> Self {
+        ShipInput {
+            thrust: Vector3::new(
+                bindings.axis(window, Action::ShipForward, Action::ShipBackward),
+                bindings.axis(window, Action::ShipUp, Action::ShipDown),
+                bindings.axis(window, Action::ShipRight, Action::ShipLeft),
+            ),
+            torque: Vector3::new(
+                bindings.axis(window, Action::ShipRollRight, Action::ShipRollLeft),
+                bindings.axis(window, Action::ShipYawLeft, Action::ShipYawRight),
+                bindings.axis(window, Action::ShipPitchUp, Action::ShipPitchDown),
+            ),
+        }
+    }
+}
+
+/// Rigid body flown by the player
+pub struct Ship {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub orientation: Quaternion,   // Ship space to world space
+    pub angular_velocity: Vector3, // Radians per second around the ship axes
+    pub scale: f32,
+
+    pub mass: f32,
+    pub inertia: Vector3,        // Moments of inertia around the ship axes
+    pub main_thrust: f32,        // Force of the main engine
+    pub reverse_thrust: f32,     // Force when braking
+    pub maneuver_thrust: f32,    // Force of the lateral and vertical thrusters
+    pub torque: f32,             // Torque of the attitude thrusters
+
+    // Flight assist: fire the thrusters to cancel rotation and sideways drift
+    pub flight_assist: bool,
+    pub assist_linear: f32,      // 1/s
+    pub assist_angular: f32,     // 1/s
+}
+
+impl Ship {
+    pub fn new(position: Vector3, velocity: Vector3, orientation: Quaternion) -> Self {
+        Ship {
+            position,
+            velocity,
+            orientation,
+            angular_velocity: Vector3::zero(),
+            scale: 0.3,
+            mass: 1.0,
+            // A long, flat ship: easy to roll, harder to pitch and yaw
+            inertia: Vector3::new(0.6, 1.2, 1.0),
+            main_thrust: 8.0,
+            reverse_thrust: 4.0,
+            maneuver_thrust: 3.0,
+            torque: 2.0,
+            flight_assist: false,
+            assist_linear: 1.5,
+            assist_angular: 3.0,
+        }
+    }
+
+    pub fn model_matrix(&self) -> Matrix {
+        create_model_matrix_from_quaternion(self.position, self.scale, self.orientation)
+    }
+
+    /// Gravitational acceleration at `position` from every attractor
+    pub fn gravity_at(position: Vector3, attractors: &[Attractor]) -> Vector3 {
+        let mut acceleration = Vector3::zero();
+        for attractor in attractors {
+            let offset = attractor.position - position;
+            let distance = offset.length().max(attractor.radius).max(1e-3);
+            acceleration += offset.normalized() * (GRAVITY * attractor.mass / (distance * distance));
+        }
+        acceleration
+    }
+
+    /// Advance the simulation by `dt` seconds.
+    /// `attractors_at(t)` gives the bodies `t` seconds into the update, so each substep sees them where they are.
+    pub fn update(&mut self, input: ShipInput, attractors_at: impl Fn(f32) -> Vec<Attractor>, dt: f32) {
+        let mut remaining = dt;
+        while remaining > 0.0 {
+            let step = remaining.min(MAX_STEP);
+            remaining -= step;
+            self.step(input, &attractors_at(dt - remaining), step);
+        }
+    }
+
+    fn step(&mut self, input: ShipInput, attractors: &[Attractor], dt: f32) {
+        // Engine forces in ship space
+        let forward_thrust = if input.thrust.x >= 0.0 { self.main_thrust } else { self.reverse_thrust };
+        let mut local_force = Vector3::new(
+            input.thrust.x * forward_thrust,
+            input.thrust.y * self.maneuver_thrust,
+            input.thrust.z * self.maneuver_thrust,
+        );
+        let mut local_torque = input.torque * self.torque;
+
+        if self.flight_assist {
+            // Cancel drift on the axes the pilot isn't using; never brake the forward motion
+            let local_velocity = self.velocity.rotate_by(self.orientation.inverted());
+            if input.thrust.y == 0.0 {
+                local_force.y -= (local_velocity.y * self.assist_linear * self.mass).clamp(-self.maneuver_thrust, self.maneuver_thrust);
+            }
+            if input.thrust.z == 0.0 {
+                local_force.z -= (local_velocity.z * self.assist_linear * self.mass).clamp(-self.maneuver_thrust, self.maneuver_thrust);
+            }
+
+            // Stop rotating around the axes that have no input
+            let assist = -self.angular_velocity * self.inertia * self.assist_angular;
+            if input.torque.x == 0.0 {
+                local_torque.x += assist.x.clamp(-self.torque, self.torque);
+            }
+            if input.torque.y == 0.0 {
+                local_torque.y += assist.y.clamp(-self.torque, self.torque);
+            }
+            if input.torque.z == 0.0 {
+                local_torque.z += assist.z.clamp(-self.torque, self.torque);
+            }
+        }
+
+        // Linear motion: engines plus gravity, semi-implicit Euler
+        let acceleration = local_force.rotate_by(self.orientation) / self.mass + Ship::gravity_at(self.position, attractors);
+        self.velocity += acceleration * dt;
+        self.position += self.velocity * dt;
+
+        // Euler's rotation equations: I·dω/dt = τ - ω × (I·ω)
+        let angular_momentum = self.angular_velocity * self.inertia;
+        let angular_acceleration = (local_torque - self.angular_velocity.cross(angular_momentum)) / self.inertia;
+        self.angular_velocity += angular_acceleration * dt;
+
+        // Rotate around the ship's own axes
+        let angle = self.angular_velocity.length() * dt;
+        if angle > 0.0 {
+            let delta = Quaternion::from_axis_angle(self.angular_velocity, angle);
+            self.orientation = (self.orientation * delta).normalized();
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// sphere.rs
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use crate::vertex::Vertex;
+
+/// Generates a UV sphere centered on the origin as a triangle list, like `Obj::get_vertex_array`.
+/// `segments` divide it around the Y axis and `rings` from pole to pole.
+/// u follows the longitude and v goes from the north pole (0) to the south pole (1).
+pub fn uv_sphere(radius: f32, segments: usize, rings: usize) -> Vec<Vertex> {
+    let segments = segments.max(3);
+    let rings = rings.max(2);
+    let mut vertices = Vec::with_capacity(segments * (rings - 1) * 6);
+
+    let point = |ring: usize, segment: usize| {
+        let u = segment as f32 / segments as f32;
+        let v = ring as f32 / rings as f32;
+        let (sin_theta, cos_theta) = (v * PI).sin_cos();
+        let (sin_phi, cos_phi) = (u * 2.0 * PI).sin_cos();
+        sphere_vertex(Vector3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi), radius, Vector2::new(u, v))
+    };
+
+    for ring in 0..rings {
+        for segment in 0..segments {
+            let a = point(ring, segment);
+            let b = point(ring + 1, segment);
+            let c = point(ring + 1, segment + 1);
+            let d = point(ring, segment + 1);
+
+            // The first and last rings are fans around the poles, their quads have a collapsed edge
+            if ring != 0 {
+                vertices.extend([a.clone(), c.clone(), d]);
+            }
+            if ring != rings - 1 {
+                vertices.extend([a, b, c]);
+            }
+        }
+    }
+
+    vertices
+}
+
+/// Generates an icosphere centered on the origin as a triangle list: an icosahedron whose
+/// triangles are split in four `subdivisions` times, pushing the new corners out to the sphere.
+/// Triangles are more even than in a UV sphere; UVs use the same longitude/latitude mapping.
+pub fn icosphere(radius: f32, subdivisions: usize) -> Vec<Vertex> {
+    let mut triangles: Vec<[Vector3; 3]> = ICOSAHEDRON_FACES
+        .iter()
+        .map(|face| face.map(icosahedron_vertex))
+        .collect();
+
+    for _ in 0..subdivisions {
+        triangles = triangles
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = ((a + b) * 0.5).normalized();
+                let bc = ((b + c) * 0.5).normalized();
+                let ca = ((c + a) * 0.5).normalized();
+                [[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    let mut vertices = Vec::with_capacity(triangles.len() * 3);
+    for corners in &triangles {
+        let mut u = corners.map(longitude);
+
+        // A triangle crossing the seam at u = 0 gets its low side moved past 1, so it doesn't wrap around backwards
+        let highest = u.iter().copied().fold(0.0, f32::max);
+        for value in &mut u {
+            if highest - *value > 0.5 {
+                *value += 1.0;
+            }
+        }
+        // The longitude of a pole is undefined, use the one of the edge it belongs to
+        for i in 0..3 {
+            if corners[i].x.abs() < 1e-6 && corners[i].z.abs() < 1e-6 {
+                u[i] = (u[(i + 1) % 3] + u[(i + 2) % 3]) * 0.5;
+            }
+        }
+
+        for i in 0..3 {
+            let v = corners[i].y.clamp(-1.0, 1.0).acos() / PI;
+            vertices.push(sphere_vertex(corners[i], radius, Vector2::new(u[i], v)));
+        }
+    }
+
+    vertices
+}
+
+/// Vertex on a sphere of `radius` in the unit `direction`. The tangent points east, where the longitude
+/// grows, and is taken from u so it is also defined at the poles.
+fn sphere_vertex(direction: Vector3, radius: f32, tex_coords: Vector2) -> Vertex {
+    let (sin_phi, cos_phi) = (tex_coords.x * 2.0 * PI).sin_cos();
+    Vertex::new_with_tangent(direction * radius, direction, tex_coords, Vector3::new(-sin_phi, 0.0, cos_phi))
+}
+
+/// u of a unit direction, matching the angle used by `uv_sphere`
+fn longitude(direction: Vector3) -> f32 {
+    let u = direction.z.atan2(direction.x) / (2.0 * PI);
+    if u < 0.0 { u + 1.0 } else { u }
+}
+
+fn icosahedron_vertex(index: usize) -> Vector3 {
+    // Three orthogonal golden rectangles
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let [x, y, z] = [
+        [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+        [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+        [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+    ][index];
+    Vector3::new(x, y, z).normalized()
+}
+
+const ICOSAHEDRON_FACES: [[usize; 3]; 20] = [
+    [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+    [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+    [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+    [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+];
@@ -0,0 +1,76 @@
+// trajectory.rs
+use raylib::prelude::*;
+use crate::ship::{Attractor, Ship};
+
+/// Future path of the ship if the engines stay off
+pub struct Prediction {
+    pub points: Vec<Vector3>,
+    pub periapsis: Option<Vector3>, // Closest approach to the dominant body
+    pub apoapsis: Option<Vector3>,  // Farthest point from the dominant body
+    pub impact: Option<Vector3>,    // Where the path first hits a body
+}
+
+/// Integrates the ship's coasting path for `duration` seconds in steps of `step`.
+/// `attractors_at(t)` gives the bodies `t` seconds into the future, so moving planets are taken into account.
+pub fn predict(ship: &Ship, attractors_at: impl Fn(f32) -> Vec<Attractor>, duration: f32, step: f32) -> Prediction {
+    let mut position = ship.position;
+    let mut velocity = ship.velocity;
+    let mut points = vec![position];
+    let mut impact = None;
+
+    // Apsides are measured against the body that pulls the hardest right now
+    let now = attractors_at(0.0);
+    let reference = dominant_attractor(position, &now);
+    let mut distances = Vec::new();
+    if let Some(index) = reference {
+        distances.push(position.distance_to(now[index].position));
+    }
+
+    let steps = (duration / step.max(1e-3)).ceil() as usize;
+    for i in 1..=steps {
+        let t = i as f32 * step;
+        let attractors = attractors_at(t);
+
+        // Same semi-implicit Euler as the ship, without thrust
+        velocity += Ship::gravity_at(position, &attractors) * step;
+        position += velocity * step;
+        points.push(position);
+
+        if let Some(index) = reference {
+            distances.push(position.distance_to(attractors[index].position));
+        }
+
+        if attractors.iter().any(|attractor| position.distance_to(attractor.position) < attractor.radius) {
+            impact = Some(position);
+            break;
+        }
+    }
+
+    let periapsis = first_extremum(&distances, |previous, current, next| current < previous && current <= next)
+        .map(|i| points[i]);
+    let apoapsis = first_extremum(&distances, |previous, current, next| current > previous && current >= next)
+        .map(|i| points[i]);
+
+    Prediction { points, periapsis, apoapsis, impact }
+}
+
+/// Index of the attractor with the strongest pull at `position`
+fn dominant_attractor(position: Vector3, attractors: &[Attractor]) -> Option<usize> {
+    attractors
+        .iter()
+        .enumerate()
+        .map(|(index, attractor)| {
+            let distance = position.distance_to(attractor.position).max(attractor.radius).max(1e-3);
+            (index, attractor.mass / (distance * distance))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// First index whose value and neighbours satisfy `is_extremum(previous, current, next)`
+fn first_extremum(values: &[f32], is_extremum: impl Fn(f32, f32, f32) -> bool) -> Option<usize> {
+    values
+        .windows(3)
+        .position(|window| is_extremum(window[0], window[1], window[2]))
+        .map(|i| i + 1)
+}
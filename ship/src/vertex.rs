@@ -8,6 +8,7 @@ pub struct Vertex {
   pub position: Vector3,
   pub normal: Vector3,
   pub tex_coords: Vector2,
+  pub tangent: Vector3, // Surface direction in which u grows, zero when the mesh doesn't have one
   pub color: Vector3,
   pub transformed_position: Vector3,
   pub transformed_normal: Vector3,
@@ -15,10 +16,15 @@ pub struct Vertex {
 
 impl Vertex {
   pub fn new(position: Vector3, normal: Vector3, tex_coords: Vector2) -> Self {
+    Vertex::new_with_tangent(position, normal, tex_coords, Vector3::new(0.0, 0.0, 0.0))
+  }
+
+  pub fn new_with_tangent(position: Vector3, normal: Vector3, tex_coords: Vector2, tangent: Vector3) -> Self {
     Vertex {
       position,
       normal,
       tex_coords,
+      tangent,
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: position,
       transformed_normal: normal,
@@ -30,6 +36,7 @@ impl Vertex {
       position,
       normal: Vector3::new(0.0, 0.0, 0.0),
       tex_coords: Vector2::new(0.0, 0.0),
+      tangent: Vector3::new(0.0, 0.0, 0.0),
       color,
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 0.0, 0.0),
@@ -48,6 +55,7 @@ impl Default for Vertex {
       position: Vector3::new(0.0, 0.0, 0.0),
       normal: Vector3::new(0.0, 1.0, 0.0),
       tex_coords: Vector2::new(0.0, 0.0),
+      tangent: Vector3::new(1.0, 0.0, 0.0),
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 1.0, 0.0),
@@ -0,0 +1,66 @@
+// viewport.rs
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::create_viewport_matrix;
+
+/// Rectangle of the framebuffer that one camera renders into
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Viewport {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Viewport { x, y, width, height }
+    }
+
+    /// The whole of a `width` × `height` framebuffer
+    pub fn full(width: i32, height: i32) -> Self {
+        Viewport::new(0, 0, width, height)
+    }
+
+    /// Left and right halves, the right one gets the odd pixel
+    pub fn split_vertical(self) -> (Viewport, Viewport) {
+        let left = self.width / 2;
+        (
+            Viewport::new(self.x, self.y, left, self.height),
+            Viewport::new(self.x + left, self.y, self.width - left, self.height),
+        )
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    /// Maps NDC onto this rectangle instead of the whole screen
+    pub fn matrix(&self) -> Matrix {
+        create_viewport_matrix(self.x as f32, self.y as f32, self.width as f32, self.height as f32)
+    }
+
+    pub fn contains(&self, point: Vector2) -> bool {
+        point.x >= self.x as f32
+            && point.x < (self.x + self.width) as f32
+            && point.y >= self.y as f32
+            && point.y < (self.y + self.height) as f32
+    }
+
+    /// Screen point relative to the top-left corner of the rectangle, for unprojecting the cursor
+    pub fn to_local(self, point: Vector2) -> Vector2 {
+        Vector2::new(point.x - self.x as f32, point.y - self.y as f32)
+    }
+
+    /// Clears color and depth inside the rectangle and keeps every following draw inside it,
+    /// so each view depth-tests only against itself. Undo with `Framebuffer::set_scissor(None)`.
+    pub fn begin(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.clear_rect(self.x, self.y, self.width, self.height);
+        self.scissor(framebuffer);
+    }
+
+    /// Keeps the following draws inside the rectangle without clearing it
+    pub fn scissor(&self, framebuffer: &mut Framebuffer) {
+        framebuffer.set_scissor(Some((self.x, self.y, self.width, self.height)));
+    }
+}
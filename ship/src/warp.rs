@@ -0,0 +1,141 @@
+// warp.rs
+use raylib::prelude::*;
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+
+// Number of light streaks drawn during the transit
+const STREAKS: u32 = 64;
+// Extra field of view at the middle of the flight, gives the tunnel feeling
+const FOV_BOOST: f32 = 0.35;
+
+/// Where a warp ends
+#[derive(Clone, Copy)]
+pub enum WarpDestination {
+    View { eye: Vector3, target: Vector3, up: Vector3 }, // A fixed viewpoint
+    Body(usize),                                          // Index of a celestial body, tracked while it moves
+    Ship,                                                 // The player's ship, tracked while it moves
+}
+
+/// Animated flight of the camera from its current view to a destination
+pub struct Warp {
+    pub destination: WarpDestination,
+    pub duration: f32, // Seconds
+    elapsed: f32,
+    start_eye: Vector3,
+    start_target: Vector3,
+    start_up: Vector3,
+    base_fov: f32,
+}
+
+impl Warp {
+    pub fn new(camera: &Camera, destination: WarpDestination, duration: f32) -> Self {
+        Warp {
+            destination,
+            duration,
+            elapsed: 0.0,
+            start_eye: camera.eye,
+            start_target: camera.target,
+            start_up: camera.up,
+            base_fov: camera.fov,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    /// Fraction of the flight completed, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Strength of the warp effect: zero at both ends, peaks with the speed in the middle
+    pub fn intensity(&self) -> f32 {
+        let t = self.progress();
+        4.0 * t * (1.0 - t)
+    }
+
+    /// Field of view the camera had before the warp
+    pub fn base_fov(&self) -> f32 {
+        self.base_fov
+    }
+
+    /// Field of view for the current point of the flight
+    pub fn fov(&self) -> f32 {
+        self.base_fov + self.intensity() * FOV_BOOST
+    }
+
+    /// Camera eye, target and up along the path. The end pose is passed every frame,
+    /// so the flight follows a destination that keeps moving.
+    pub fn pose(&self, end_eye: Vector3, end_target: Vector3, end_up: Vector3) -> (Vector3, Vector3, Vector3) {
+        let t = ease_in_out(self.progress());
+
+        // Quadratic Bézier lifted above the straight line, so the flight arcs over the system
+        let length = self.start_eye.distance_to(end_eye);
+        let control = self.start_eye.lerp(end_eye, 0.5) + Vector3::new(0.0, length * 0.3, 0.0);
+        let eye = self.start_eye.lerp(control, t).lerp(control.lerp(end_eye, t), t);
+
+        // The view turns towards the destination faster than the camera travels
+        let look = ease_in_out(t.sqrt());
+        let target = self.start_target.lerp(end_target, look);
+
+        let up = self.start_up.lerp(end_up, t);
+        let up = if up.length() > 1e-3 { up.normalized() } else { end_up };
+
+        (eye, target, up)
+    }
+
+    /// Light streaks rushing out from the center of the screen, drawn over the finished frame
+    pub fn draw_streaks(&self, framebuffer: &mut Framebuffer) {
+        let intensity = self.intensity();
+        if intensity <= 0.01 {
+            return;
+        }
+
+        let center = Vector2::new(framebuffer.width as f32 * 0.5, framebuffer.height as f32 * 0.5);
+        let max_radius = center.length();
+        let color = Vector3::new(0.75, 0.85, 1.0);
+
+        for streak in 0..STREAKS {
+            let angle = hash(streak * 3) * 2.0 * std::f32::consts::PI;
+            let speed = 0.6 + hash(streak * 3 + 1);
+            let phase = (hash(streak * 3 + 2) + self.elapsed * speed * 1.5).fract();
+
+            // Streaks accelerate and stretch as they move away from the center
+            let inner = phase * phase * max_radius;
+            let outer = inner + max_radius * 0.3 * intensity * phase;
+            let direction = Vector2::new(angle.cos(), angle.sin());
+            let start = center + direction * inner;
+            let end = center + direction * outer;
+
+            framebuffer.draw_line_aa(
+                Vector3::new(start.x, start.y, -1.0),
+                Vector3::new(end.x, end.y, -1.0),
+                color,
+                intensity * phase,
+                1.5,
+            );
+        }
+    }
+}
+
+/// Smoothstep easing: starts and ends with zero speed
+fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Cheap deterministic pseudo-random value in [0, 1) for streak `n`
+fn hash(n: u32) -> f32 {
+    let mut x = n.wrapping_mul(0x9E37_79B9) ^ 0x85EB_CA6B;
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x2C1B_3C6D);
+    x ^= x >> 12;
+    (x & 0x00FF_FFFF) as f32 / 0x0100_0000 as f32
+}